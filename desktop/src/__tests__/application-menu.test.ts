@@ -1,3 +1,4 @@
+import type { MenuItemConstructorOptions } from "electron";
 import { describe, expect, test, vi } from "vitest";
 import { createApplicationMenuTemplate, readChosenDirectory } from "../runtime/application-menu.js";
 
@@ -11,7 +12,11 @@ describe("application-menu", () => {
         enabled: true,
         label: "Check for Updates…",
       },
+      serverDebugEnabled: false,
+      serverAutoRestartEnabled: true,
       onCheckForUpdates,
+      onToggleServerDebug: vi.fn(),
+      onToggleServerAutoRestart: vi.fn(),
     });
 
     const editMenu = template.find((item) => item.label === "Edit");
@@ -29,6 +34,30 @@ describe("application-menu", () => {
     ]);
   });
 
+  test("reflects and toggles server debug mode", () => {
+    const onToggleServerDebug = vi.fn();
+    const template = createApplicationMenuTemplate({
+      appName: "Paperclip",
+      platform: "linux",
+      updateMenuState: {
+        enabled: false,
+        label: "Check for Updates…",
+      },
+      serverDebugEnabled: true,
+      serverAutoRestartEnabled: true,
+      onCheckForUpdates: vi.fn(),
+      onToggleServerDebug,
+      onToggleServerAutoRestart: vi.fn(),
+    });
+
+    const submenu = template[0]?.submenu as MenuItemConstructorOptions[];
+    const debugItem = submenu.find((item) => item.id === "paperclip-server-debug-mode");
+    expect(debugItem).toMatchObject({ type: "checkbox", checked: true });
+
+    debugItem?.click?.({ checked: false } as never, undefined, {} as never);
+    expect(onToggleServerDebug).toHaveBeenCalledWith(false);
+  });
+
   test("returns the first selected directory path", () => {
     expect(
       readChosenDirectory({
@@ -0,0 +1,52 @@
+import { afterEach, describe, expect, test } from "vitest";
+import { mkdtemp, readFile, rm } from "node:fs/promises";
+import os from "node:os";
+import path from "node:path";
+import { createDesktopEventJournal, resolveDesktopEventJournalPath } from "../runtime/desktop-event-journal.js";
+import type { DesktopEvent } from "../runtime/desktop-events.js";
+
+const tempDirs: string[] = [];
+
+async function createTempDir(prefix: string) {
+  const dir = await mkdtemp(path.join(os.tmpdir(), prefix));
+  tempDirs.push(dir);
+  return dir;
+}
+
+function startingEvent(sequence: number, at: string): DesktopEvent {
+  return { sequence, type: "server-starting", at, payload: { elapsedMs: sequence * 1_000 } };
+}
+
+afterEach(async () => {
+  await Promise.all(tempDirs.splice(0).map((dir) => rm(dir, { recursive: true, force: true })));
+});
+
+describe("desktop-event-journal", () => {
+  test("persists events across journal instances and filters by time", async () => {
+    const dir = await createTempDir("paperclip-event-journal-");
+    const filePath = resolveDesktopEventJournalPath(dir);
+
+    const journal = createDesktopEventJournal({ filePath });
+    journal.append(startingEvent(1, "2026-01-01T00:00:00.000Z"));
+    journal.append(startingEvent(2, "2026-01-01T01:00:00.000Z"));
+
+    const reopened = createDesktopEventJournal({ filePath });
+    expect(reopened.read().map((event) => event.sequence)).toEqual([1, 2]);
+    expect(reopened.read({ since: "2026-01-01T00:30:00.000Z" }).map((event) => event.sequence)).toEqual([2]);
+    expect(reopened.lastSequence()).toBe(2);
+  });
+
+  test("keeps the journal bounded", async () => {
+    const dir = await createTempDir("paperclip-event-journal-");
+    const filePath = resolveDesktopEventJournalPath(dir);
+    const journal = createDesktopEventJournal({ filePath, maxEntries: 3 });
+
+    for (let sequence = 1; sequence <= 6; sequence += 1) {
+      journal.append(startingEvent(sequence, new Date(Date.UTC(2026, 0, 1, sequence)).toISOString()));
+    }
+
+    expect(journal.read().map((event) => event.sequence)).toEqual([4, 5, 6]);
+    const lines = (await readFile(filePath, "utf8")).trim().split("\n");
+    expect(lines).toHaveLength(3);
+  });
+});
@@ -0,0 +1,42 @@
+import { describe, expect, test, vi } from "vitest";
+import { createDesktopEventBus, toDesktopEventChannel } from "../runtime/desktop-events.js";
+
+describe("desktop-events", () => {
+  test("delivers events to subscribers with increasing sequence numbers", () => {
+    const bus = createDesktopEventBus({ now: () => new Date("2026-01-01T00:00:00.000Z") });
+    const listener = vi.fn();
+    bus.subscribe(listener);
+
+    bus.emit("server-starting", { elapsedMs: 5_000 });
+    bus.emit("server-restarted", { apiUrl: "http://127.0.0.1:3100", attempt: 1 });
+
+    expect(listener.mock.calls.map(([event]) => [event.sequence, event.type])).toEqual([
+      [1, "server-starting"],
+      [2, "server-restarted"],
+    ]);
+    expect(listener.mock.calls[0]?.[0].at).toBe("2026-01-01T00:00:00.000Z");
+  });
+
+  test("replays recent lifecycle events for late subscribers but not log lines", () => {
+    const bus = createDesktopEventBus({ replayLimit: 2 });
+    bus.emit("server-starting", { elapsedMs: 5_000 });
+    bus.emit("server-log", { stream: "stdout", line: "listening" });
+    bus.emit("server-starting", { elapsedMs: 10_000 });
+    bus.emit("server-restarted", { apiUrl: "http://127.0.0.1:3100", attempt: 1 });
+
+    expect(bus.replay().map((event) => event.sequence)).toEqual([3, 4]);
+    expect(bus.replay({ types: ["server-restarted"] }).map((event) => event.type)).toEqual(["server-restarted"]);
+    expect(bus.replay({ afterSequence: 3 }).map((event) => event.sequence)).toEqual([4]);
+  });
+
+  test("resumes numbering after a previous session's last sequence", () => {
+    const bus = createDesktopEventBus();
+    bus.resumeFrom(41);
+
+    expect(bus.emit("server-starting", { elapsedMs: 5_000 }).sequence).toBe(42);
+  });
+
+  test("maps event types onto IPC channels", () => {
+    expect(toDesktopEventChannel("server-crashed")).toBe("paperclip-desktop:server-crashed");
+  });
+});
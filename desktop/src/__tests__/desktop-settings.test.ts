@@ -0,0 +1,89 @@
+import { afterEach, describe, expect, test } from "vitest";
+import { mkdtemp, rm, writeFile } from "node:fs/promises";
+import os from "node:os";
+import path from "node:path";
+import {
+  DEFAULT_DESKTOP_SETTINGS,
+  loadDesktopSettings,
+  parseDesktopSettings,
+  pickRendererSettingsPatch,
+  saveDesktopSettings,
+} from "../runtime/desktop-settings.js";
+
+const tempDirs: string[] = [];
+
+async function createTempDir(prefix: string) {
+  const dir = await mkdtemp(path.join(os.tmpdir(), prefix));
+  tempDirs.push(dir);
+  return dir;
+}
+
+afterEach(async () => {
+  await Promise.all(tempDirs.splice(0).map((dir) => rm(dir, { recursive: true, force: true })));
+});
+
+describe("desktop-settings", () => {
+  test("returns defaults when no settings file exists", async () => {
+    const dir = await createTempDir("paperclip-desktop-settings-");
+    expect(loadDesktopSettings(path.join(dir, "missing.json"))).toEqual(DEFAULT_DESKTOP_SETTINGS);
+  });
+
+  test("persists and reloads saved settings", async () => {
+    const dir = await createTempDir("paperclip-desktop-settings-");
+    const filePath = path.join(dir, "desktop-settings.json");
+    const expectedSettings = {
+      ...DEFAULT_DESKTOP_SETTINGS,
+      serverLogLevel: "warn" as const,
+      serverDebug: true,
+      serverAutoRestart: false,
+      serverStopGracePeriodMs: 2_500,
+      serverStartupTimeoutMs: 180_000,
+      serverHealthPath: "/readyz",
+      serverMaxOldSpaceMb: 2_048,
+      serverPortRange: { start: 3100, end: 3199 },
+      serverEnv: { HTTPS_PROXY: "http://proxy.internal:8080" },
+    };
+
+    saveDesktopSettings(filePath, expectedSettings);
+
+    expect(loadDesktopSettings(filePath)).toEqual(expectedSettings);
+  });
+
+  test("falls back to defaults for invalid values", async () => {
+    const dir = await createTempDir("paperclip-desktop-settings-");
+    const filePath = path.join(dir, "desktop-settings.json");
+    await writeFile(filePath, JSON.stringify({
+      serverLogLevel: "verbose",
+      serverDebug: "yes",
+      serverStopGracePeriodMs: -1,
+      serverHealthPath: "http://example.com/health",
+      serverPortRange: { start: 3200, end: 3100 },
+      serverEnv: ["nope"],
+    }));
+
+    expect(loadDesktopSettings(filePath)).toEqual(DEFAULT_DESKTOP_SETTINGS);
+  });
+
+  test("applies valid fields of a partial update and keeps the rest", () => {
+    const current = { ...DEFAULT_DESKTOP_SETTINGS, serverMaxOldSpaceMb: 4_096, serverEnv: { FOO: "bar" } };
+
+    expect(parseDesktopSettings({ serverStartupTimeoutMs: 30_000, serverLogLevel: "verbose" }, current)).toEqual({
+      ...current,
+      serverStartupTimeoutMs: 30_000,
+    });
+    expect(parseDesktopSettings({ serverMaxOldSpaceMb: null, serverEnv: {} }, current)).toEqual({
+      ...current,
+      serverMaxOldSpaceMb: null,
+      serverEnv: {},
+    });
+  });
+
+  test("keeps launch-affecting settings out of renderer patches", () => {
+    expect(pickRendererSettingsPatch({
+      serverStartupTimeoutMs: 30_000,
+      serverEnv: { NODE_OPTIONS: "--require /tmp/x.js" },
+      serverMaxOldSpaceMb: 8_192,
+    })).toEqual({ serverStartupTimeoutMs: 30_000 });
+    expect(pickRendererSettingsPatch(null)).toEqual({});
+  });
+});
@@ -0,0 +1,69 @@
+import { describe, expect, test } from "vitest";
+import { analyzeStartupFailure } from "../runtime/failure-analysis.js";
+
+describe("failure-analysis", () => {
+  test("classifies port conflicts from server output", () => {
+    expect(
+      analyzeStartupFailure({
+        exitInfo: { code: 1, signal: null },
+        logLines: ["Error: listen EADDRINUSE: address already in use 127.0.0.1:3100"],
+      }).failureKind,
+    ).toBe("port_in_use");
+  });
+
+  test("classifies aborts as out of memory only when the heap error is logged", () => {
+    expect(
+      analyzeStartupFailure({
+        exitInfo: { code: null, signal: "SIGABRT" },
+        logLines: ["FATAL ERROR: Reached heap limit Allocation failed - JavaScript heap out of memory"],
+      }).failureKind,
+    ).toBe("out_of_memory");
+    expect(
+      analyzeStartupFailure({
+        exitInfo: { code: null, signal: "SIGABRT" },
+        logLines: [],
+      }).failureKind,
+    ).toBe("server_crash");
+  });
+
+  test("treats a live server without health as a timeout", () => {
+    const analysis = analyzeStartupFailure({ exitInfo: null, logLines: ["booting"] });
+    expect(analysis.failureKind).toBe("startup_timeout");
+    expect(analysis.remediation).toMatch(/did not become healthy/);
+  });
+
+  test("prefers a timeout over log patterns while the server is still running", () => {
+    expect(
+      analyzeStartupFailure({
+        exitInfo: null,
+        logLines: ["plugin worker: listen EADDRINUSE: address already in use 127.0.0.1:9229"],
+      }).failureKind,
+    ).toBe("startup_timeout");
+  });
+
+  test("falls back to a generic crash for unrecognized exits", () => {
+    expect(
+      analyzeStartupFailure({
+        exitInfo: { code: 1, signal: null },
+        logLines: ["codex binary missing"],
+      }).failureKind,
+    ).toBe("server_crash");
+  });
+
+  test("only reads database failures from embedded Postgres startup errors", () => {
+    expect(
+      analyzeStartupFailure({
+        exitInfo: { code: 1, signal: null },
+        logLines: [
+          "Error: Failed to start embedded PostgreSQL on port 54329 Recent embedded Postgres logs: FATAL: lock file exists",
+        ],
+      }).failureKind,
+    ).toBe("database_unavailable");
+    expect(
+      analyzeStartupFailure({
+        exitInfo: { code: 1, signal: null },
+        logLines: ["postgres adapter plugin error: unsupported option"],
+      }).failureKind,
+    ).toBe("server_crash");
+  });
+});
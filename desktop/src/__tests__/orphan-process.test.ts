@@ -0,0 +1,63 @@
+import { afterEach, describe, expect, test } from "vitest";
+import { spawn } from "node:child_process";
+import { mkdtemp, rm, writeFile } from "node:fs/promises";
+import { existsSync } from "node:fs";
+import os from "node:os";
+import path from "node:path";
+import {
+  cleanupOrphanedServer,
+  isProcessAlive,
+  resolveServerLockPath,
+  writeServerLock,
+} from "../runtime/orphan-process.js";
+
+const tempDirs: string[] = [];
+
+async function createTempDir(prefix: string) {
+  const dir = await mkdtemp(path.join(os.tmpdir(), prefix));
+  tempDirs.push(dir);
+  return dir;
+}
+
+afterEach(async () => {
+  await Promise.all(tempDirs.splice(0).map((dir) => rm(dir, { recursive: true, force: true })));
+});
+
+describe("orphan-process", () => {
+  test("clears a lock whose process is gone", async () => {
+    const dir = await createTempDir("paperclip-orphan-");
+    const lockPath = resolveServerLockPath(dir);
+    const lock = { pid: 2 ** 22 + 17, port: 3100, serverEntry: "/tmp/server/dist/index.js", startedAt: 1 };
+    writeServerLock(lockPath, lock);
+
+    await expect(cleanupOrphanedServer({ lockPath })).resolves.toEqual({ status: "stale", lock });
+    expect(existsSync(lockPath)).toBe(false);
+  });
+
+  test("leaves unrelated processes alone when a PID was recycled", async () => {
+    const dir = await createTempDir("paperclip-orphan-");
+    const lockPath = resolveServerLockPath(dir);
+    writeServerLock(lockPath, { pid: process.pid, port: 3100, serverEntry: "/tmp/server/dist/index.js", startedAt: 1 });
+
+    const result = await cleanupOrphanedServer({ lockPath, readCommand: async () => "/usr/bin/something-else" });
+
+    expect(result.status).toBe("foreign");
+    expect(isProcessAlive(process.pid)).toBe(true);
+  });
+
+  test("terminates an orphaned server that still runs our entry", async () => {
+    const dir = await createTempDir("paperclip-orphan-");
+    const serverEntry = path.join(dir, "index.js");
+    await writeFile(serverEntry, "setInterval(() => {}, 1000);");
+    const orphan = spawn(process.execPath, [serverEntry], { stdio: "ignore" });
+    const lockPath = resolveServerLockPath(dir);
+    writeServerLock(lockPath, { pid: orphan.pid!, port: 3100, serverEntry, startedAt: Date.now() });
+
+    const exited = new Promise((resolve) => orphan.once("exit", resolve));
+    const result = await cleanupOrphanedServer({ lockPath, stopTimeoutMs: 2_000 });
+
+    expect(result.status).toBe("killed");
+    await exited;
+    expect(existsSync(lockPath)).toBe(false);
+  });
+});
@@ -0,0 +1,24 @@
+import { describe, expect, test } from "vitest";
+import { parsePsStatsOutput, readProcessStats } from "../runtime/process-stats.js";
+
+describe("process-stats", () => {
+  test("parses rss and cpu columns from ps output", () => {
+    expect(parsePsStatsOutput("  20480  12.5\n")).toEqual({ cpuPercent: 12.5, rssBytes: 20_971_520 });
+    expect(parsePsStatsOutput("")).toBeNull();
+    expect(parsePsStatsOutput("garbage")).toBeNull();
+  });
+
+  test("reads stats for the current process", async () => {
+    const stats = await readProcessStats({
+      label: "self",
+      pid: process.pid,
+      startedAt: 1_000,
+      now: 6_500,
+    });
+
+    expect(stats).toMatchObject({ label: "self", pid: process.pid, uptimeSeconds: 5 });
+    if (process.platform !== "win32") {
+      expect(stats.rssBytes).toBeGreaterThan(0);
+    }
+  });
+});
@@ -0,0 +1,49 @@
+import { afterEach, describe, expect, test, vi } from "vitest";
+import { mkdtemp, readFile, rm, writeFile } from "node:fs/promises";
+import os from "node:os";
+import path from "node:path";
+import { createServerOutputLog } from "../runtime/server-output-log.js";
+
+const tempDirs: string[] = [];
+
+async function createTempDir(prefix: string) {
+  const dir = await mkdtemp(path.join(os.tmpdir(), prefix));
+  tempDirs.push(dir);
+  return dir;
+}
+
+afterEach(async () => {
+  await Promise.all(tempDirs.splice(0).map((dir) => rm(dir, { recursive: true, force: true })));
+});
+
+describe("server-output-log", () => {
+  test("appends to an existing log below the size limit", async () => {
+    const dir = await createTempDir("paperclip-server-output-");
+    const filePath = path.join(dir, "server-output.log");
+    await writeFile(filePath, "previous run\n");
+
+    const log = createServerOutputLog({ filePath, maxBytes: 1_024 });
+    log.write("next run\n");
+    log.end();
+
+    await vi.waitFor(async () => {
+      expect(await readFile(filePath, "utf8")).toBe("previous run\nnext run\n");
+    });
+  });
+
+  test("rotates to a single backup once the size limit is reached", async () => {
+    const dir = await createTempDir("paperclip-server-output-");
+    const filePath = path.join(dir, "server-output.log");
+    await writeFile(filePath, "oldest\n");
+
+    const log = createServerOutputLog({ filePath, maxBytes: 10 });
+    log.write("0123456789\n");
+    log.write("latest\n");
+    log.end();
+
+    await vi.waitFor(async () => {
+      expect(await readFile(`${filePath}.1`, "utf8")).toBe("oldest\n0123456789\n");
+      expect(await readFile(filePath, "utf8")).toBe("latest\n");
+    });
+  });
+});
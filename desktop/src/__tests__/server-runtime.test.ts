@@ -1,12 +1,20 @@
-import { afterEach, describe, expect, test } from "vitest";
-import { mkdtemp, mkdir, rm, writeFile } from "node:fs/promises";
+import { afterEach, describe, expect, test, vi } from "vitest";
+import { mkdtemp, mkdir, readFile, rm, writeFile } from "node:fs/promises";
+import http from "node:http";
 import os from "node:os";
 import path from "node:path";
+import { isProcessAlive } from "../runtime/orphan-process.js";
 import {
   augmentDesktopPath,
   buildDesktopServerEnv,
+  buildServerExecArgv,
+  formatCommandLine,
+  ManagedServerStartupError,
+  parseServerHealth,
   resolveDesktopPaperclipHome,
+  resolveDesktopServerOutputLogPath,
   resolveDesktopServerRoot,
+  selectServerPort,
   startManagedServer,
 } from "../runtime/server-runtime.js";
 
@@ -24,6 +32,29 @@ async function writeServerEntry(serverRoot: string, source: string) {
   await writeFile(path.join(distDir, "index.js"), source);
 }
 
+// Answers health checks with {status:"ok"} and exits cleanly on SIGTERM unless told otherwise.
+async function writeHealthyServerEntry(
+  serverRoot: string,
+  extra: { prelude?: string; readyPath?: string; listenDelayMs?: number; ignoreSigterm?: boolean } = {},
+) {
+  const status = extra.readyPath ? `req.url === ${JSON.stringify(extra.readyPath)} ? 200 : 503` : "200";
+  await writeServerEntry(
+    serverRoot,
+    `
+      const http = require("node:http");
+      ${extra.prelude ?? ""}
+      const server = http.createServer((req, res) => {
+        res.writeHead(${status}, { "content-type": "application/json" });
+        res.end(JSON.stringify({ status: "ok" }));
+      });
+      setTimeout(() => server.listen(Number(process.env.PORT), process.env.HOST), ${extra.listenDelayMs ?? 0});
+      process.on("SIGTERM", () => {
+        ${extra.ignoreSigterm ? "" : "server.close(() => process.exit(0));"}
+      });
+    `,
+  );
+}
+
 afterEach(async () => {
   await Promise.all(tempDirs.splice(0).map((dir) => rm(dir, { recursive: true, force: true })));
 });
@@ -69,9 +100,49 @@ describe("server-runtime", () => {
     expect(env.PAPERCLIP_DEPLOYMENT_MODE).toBe("local_trusted");
     expect(env.PAPERCLIP_DEPLOYMENT_EXPOSURE).toBe("private");
     expect(env.PAPERCLIP_INSTANCE_ID).toBe("default");
+    expect(env.PAPERCLIP_LOG_LEVEL).toBe("debug");
     expect(env.PATH?.split(path.delimiter)[0]).toBe("/custom/bin");
   });
 
+  test("maps log level and debug mode onto the server environment", () => {
+    const baseInput = {
+      baseEnv: { PATH: "/custom/bin", HOME: "/tmp/test-home" },
+      paperclipHome: "/tmp/paperclip-home",
+      port: 4310,
+    };
+
+    expect(buildDesktopServerEnv({ ...baseInput, logLevel: "warn" }).PAPERCLIP_LOG_LEVEL).toBe("warn");
+    expect(buildDesktopServerEnv({ ...baseInput, logLevel: "warn", debug: true }).PAPERCLIP_LOG_LEVEL).toBe("trace");
+  });
+
+  test("applies user environment overrides without replacing desktop-managed keys", () => {
+    const env = buildDesktopServerEnv({
+      baseEnv: { PATH: "/custom/bin", HOME: "/tmp/test-home", NODE_OPTIONS: "--inspect" },
+      paperclipHome: "/tmp/paperclip-home",
+      port: 4310,
+      extraEnv: {
+        NODE_OPTIONS: "--dns-result-order=ipv4first",
+        HTTPS_PROXY: "http://proxy.internal:8080",
+        PORT: "9999",
+        PATH: "/proxy/bin",
+      },
+    });
+
+    expect(env.NODE_OPTIONS).toBe("--dns-result-order=ipv4first");
+    expect(env.HTTPS_PROXY).toBe("http://proxy.internal:8080");
+    expect(env.PORT).toBe("4310");
+    expect(env.PATH?.split(path.delimiter)[0]).toBe("/proxy/bin");
+  });
+
+  test("builds node flags for heap limits and debug mode", () => {
+    expect(buildServerExecArgv({})).toEqual([]);
+    expect(buildServerExecArgv({ maxOldSpaceMb: 2048, debug: true })).toEqual([
+      "--max-old-space-size=2048",
+      "--trace-warnings",
+      "--trace-uncaught",
+    ]);
+  });
+
   test("resolves runtime roots for packaged and unpackaged app modes", () => {
     expect(
       resolveDesktopServerRoot({
@@ -90,6 +161,28 @@ describe("server-runtime", () => {
     ).toBe("/Users/chason/paperclip/desktop/.stage/server");
   });
 
+  test("parses the health payload defensively", () => {
+    expect(parseServerHealth(null)).toEqual({
+      status: "unknown",
+      version: null,
+      deploymentMode: null,
+      features: {},
+    });
+    expect(
+      parseServerHealth({
+        status: "ok",
+        version: "0.3.1",
+        deploymentMode: "local_trusted",
+        features: { companyDeletionEnabled: false, ignored: "yes" },
+      }),
+    ).toEqual({
+      status: "ok",
+      version: "0.3.1",
+      deploymentMode: "local_trusted",
+      features: { companyDeletionEnabled: false },
+    });
+  });
+
   test("starts a managed server, waits for health, and shuts it down cleanly", async () => {
     const serverRoot = await createTempDir("paperclip-desktop-server-");
     const userDataDir = await createTempDir("paperclip-desktop-userdata-");
@@ -99,15 +192,18 @@ describe("server-runtime", () => {
       `
         const http = require("node:http");
         const server = http.createServer((req, res) => {
+          console.log(req.method + " " + req.url);
           if (req.url === "/api/health") {
             res.writeHead(200, { "content-type": "application/json" });
-            res.end(JSON.stringify({ ok: true }));
+            res.end(JSON.stringify({ status: "ok", version: "1.2.3", features: { companyDeletionEnabled: true } }));
             return;
           }
           res.writeHead(404);
           res.end("not found");
         });
-        server.listen(Number(process.env.PORT), process.env.HOST);
+        server.listen(Number(process.env.PORT), process.env.HOST, () => {
+          console.error("server listening");
+        });
         process.on("SIGTERM", () => {
           server.close(() => process.exit(0));
         });
@@ -122,15 +218,145 @@ describe("server-runtime", () => {
     });
 
     expect(handle.paperclipHome).toBe(resolveDesktopPaperclipHome(userDataDir));
+    expect(handle.apiUrl).toBe(`http://127.0.0.1:${handle.port}`);
+    expect(handle.health).toEqual({
+      status: "ok",
+      version: "1.2.3",
+      deploymentMode: null,
+      features: { companyDeletionEnabled: true },
+    });
+
+    expect(handle.getRecentLogLines()).toContain("server listening");
 
+    const onLogLine = vi.fn();
+    const unsubscribe = handle.onLogLine(onLogLine);
     const healthResponse = await fetch(new URL("/api/health", handle.apiUrl));
     expect(healthResponse.status).toBe(200);
-    expect(await healthResponse.json()).toEqual({ ok: true });
+    await vi.waitFor(() => {
+      expect(onLogLine).toHaveBeenCalledWith({ stream: "stdout", line: "GET /api/health" });
+    });
+    unsubscribe();
+    expect(handle.getRecentLogLines(1)).toEqual(["GET /api/health"]);
 
     await handle.stop();
     await expect(handle.whenExit).resolves.toMatchObject({ code: 0 });
   });
 
+  test("waits for a custom readiness path", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-readyz-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeHealthyServerEntry(serverRoot, { readyPath: "/readyz" });
+
+    const handle = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4384,
+      startupTimeoutMs: 5_000,
+      healthPath: "/readyz",
+    });
+
+    expect(handle.health.status).toBe("ok");
+    await handle.stop();
+  });
+
+  test("reports startup progress while the server is slow to bind", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-slow-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeHealthyServerEntry(serverRoot, { listenDelayMs: 1_200 });
+
+    const onStartupProgress = vi.fn();
+    const handle = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4387,
+      startupTimeoutMs: 5_000,
+      startupProgressIntervalMs: 400,
+      onStartupProgress,
+    });
+
+    expect(onStartupProgress).toHaveBeenCalled();
+    expect(onStartupProgress.mock.calls[0]?.[0]).toBeGreaterThanOrEqual(400);
+    await handle.stop();
+  });
+
+  test("force-kills a server that ignores SIGTERM after the grace period", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-stubborn-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeHealthyServerEntry(serverRoot, { ignoreSigterm: true });
+
+    const handle = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4385,
+      startupTimeoutMs: 5_000,
+      stopTimeoutMs: 200,
+    });
+
+    await handle.stop();
+    await expect(handle.whenExit).resolves.toMatchObject({ signal: "SIGKILL" });
+  });
+
+  test.skipIf(process.platform === "win32")("stops helper processes spawned by the server", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-helpers-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeHealthyServerEntry(serverRoot, {
+      prelude: `
+        const { spawn } = require("node:child_process");
+        const helper = spawn(process.execPath, ["-e", "setInterval(() => {}, 1000)"], { stdio: "ignore" });
+        console.log("helper pid " + helper.pid);
+      `,
+    });
+
+    const handle = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4388,
+      startupTimeoutMs: 5_000,
+    });
+    const helperLine = handle.getRecentLogLines().find((line) => line.startsWith("helper pid "));
+    const helperPid = Number(helperLine?.slice("helper pid ".length));
+    expect(helperPid).toBeGreaterThan(0);
+
+    await handle.stop();
+    await vi.waitFor(() => {
+      expect(isProcessAlive(helperPid)).toBe(false);
+    });
+  });
+
+  test.skipIf(process.platform === "win32")("stops helper processes left behind by a crashed server", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-crash-helpers-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeHealthyServerEntry(serverRoot, {
+      prelude: `
+        const { spawn } = require("node:child_process");
+        const helper = spawn(process.execPath, ["-e", "setInterval(() => {}, 1000)"], { stdio: "ignore" });
+        console.log("helper pid " + helper.pid);
+        setTimeout(() => process.exit(1), 1_000);
+      `,
+    });
+
+    const handle = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4391,
+      startupTimeoutMs: 5_000,
+      stopTimeoutMs: 500,
+    });
+    const helperLine = handle.getRecentLogLines().find((line) => line.startsWith("helper pid "));
+    const helperPid = Number(helperLine?.slice("helper pid ".length));
+    expect(helperPid).toBeGreaterThan(0);
+
+    await expect(handle.whenExit).resolves.toMatchObject({ code: 1 });
+    await vi.waitFor(() => {
+      expect(isProcessAlive(helperPid)).toBe(false);
+    });
+  });
+
   test("surfaces recent logs when the packaged server exits during startup", async () => {
     const serverRoot = await createTempDir("paperclip-desktop-fail-");
     const userDataDir = await createTempDir("paperclip-desktop-userdata-");
@@ -152,4 +378,183 @@ describe("server-runtime", () => {
       }),
     ).rejects.toThrow(/Recent server logs:[\s\S]*codex binary missing/);
   });
+
+  test("strips terminal color codes from buffered log lines", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-ansi-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeServerEntry(
+      serverRoot,
+      `
+        console.error("\\u001b[31mERROR\\u001b[39m: codex binary missing");
+        setTimeout(() => process.exit(1), 50);
+      `,
+    );
+
+    const error = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4382,
+      startupTimeoutMs: 1_000,
+    }).catch((caught: unknown) => caught);
+
+    expect(error).toBeInstanceOf(ManagedServerStartupError);
+    expect(error).toMatchObject({ logLines: ["ERROR: codex binary missing"] });
+  });
+
+  test("persists raw server output next to server.log", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-output-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeServerEntry(
+      serverRoot,
+      `
+        console.log("stdout line");
+        console.error("stderr line");
+        setTimeout(() => process.exit(1), 50);
+      `,
+    );
+
+    await expect(
+      startManagedServer({
+        userDataPath: userDataDir,
+        serverRoot,
+        preferredPort: 4382,
+        startupTimeoutMs: 1_000,
+      }),
+    ).rejects.toThrow(ManagedServerStartupError);
+
+    const outputPath = resolveDesktopServerOutputLogPath(resolveDesktopPaperclipHome(userDataDir));
+    await vi.waitFor(async () => {
+      const output = await readFile(outputPath, "utf8");
+      expect(output).toContain("stdout line");
+      expect(output).toContain("stderr line");
+    });
+  });
+
+  test("falls back to a free port when the preferred one is busy", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-busy-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+    const blocker = http.createServer();
+    await new Promise<void>((resolve) => blocker.listen(4386, "127.0.0.1", resolve));
+
+    await writeHealthyServerEntry(serverRoot);
+
+    try {
+      const handle = await startManagedServer({
+        userDataPath: userDataDir,
+        serverRoot,
+        preferredPort: 4386,
+        startupTimeoutMs: 5_000,
+      });
+      expect(handle.port).not.toBe(4386);
+      await handle.stop();
+    } finally {
+      await new Promise<void>((resolve) => blocker.close(() => resolve()));
+    }
+  });
+
+  test("keeps port selection inside the configured range", async () => {
+    const blocker = http.createServer();
+    await new Promise<void>((resolve) => blocker.listen(4392, "127.0.0.1", resolve));
+
+    try {
+      await expect(selectServerPort({ preferredPort: 4392, portRange: { start: 4392, end: 4393 } })).resolves.toBe(4393);
+      await expect(selectServerPort({ preferredPort: 3100, portRange: { start: 4392, end: 4393 } })).resolves.toBe(4393);
+      await expect(selectServerPort({ portRange: { start: 4392, end: 4392 } })).rejects.toThrow(/No free port/);
+    } finally {
+      await new Promise<void>((resolve) => blocker.close(() => resolve()));
+    }
+  });
+
+  test("times out with the command line when the server never binds", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-hang-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeServerEntry(
+      serverRoot,
+      `
+        console.error("waiting on a lock that never clears");
+        setInterval(() => {}, 1000);
+      `,
+    );
+
+    const error = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4389,
+      startupTimeoutMs: 700,
+      stopTimeoutMs: 500,
+    }).catch((caught: unknown) => caught);
+
+    expect(error).toBeInstanceOf(ManagedServerStartupError);
+    expect(error).toMatchObject({
+      failureKind: "startup_timeout",
+      commandLine: expect.stringContaining(path.join(serverRoot, "dist", "index.js")),
+      logLines: ["waiting on a lock that never clears"],
+    });
+  });
+
+  test("times out when the server accepts health requests but never answers", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-stuck-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeServerEntry(
+      serverRoot,
+      `
+        const http = require("node:http");
+        const server = http.createServer(() => {});
+        server.listen(Number(process.env.PORT), process.env.HOST);
+      `,
+    );
+
+    const startedAt = Date.now();
+    const error = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4390,
+      startupTimeoutMs: 1_000,
+      stopTimeoutMs: 500,
+    }).catch((caught: unknown) => caught);
+
+    expect(error).toBeInstanceOf(ManagedServerStartupError);
+    expect(error).toMatchObject({ failureKind: "startup_timeout" });
+    expect(Date.now() - startedAt).toBeLessThan(4_000);
+  });
+
+  test("shell-quotes command line parts and prefixes environment overrides", () => {
+    expect(formatCommandLine(["/Applications/Paperclip.app/Contents/MacOS/Paperclip Helper", "dist/index.js"])).toBe(
+      "'/Applications/Paperclip.app/Contents/MacOS/Paperclip Helper' dist/index.js",
+    );
+    expect(formatCommandLine(["/Users/$USER/it's/index.js"])).toBe("'/Users/$USER/it'\\''s/index.js'");
+    expect(formatCommandLine(["/Applications/Paperclip.app/Contents/MacOS/Paperclip", "--max-old-space-size=4096"], {
+      ELECTRON_RUN_AS_NODE: "1",
+    })).toBe("ELECTRON_RUN_AS_NODE=1 /Applications/Paperclip.app/Contents/MacOS/Paperclip --max-old-space-size=4096");
+  });
+
+  test("classifies startup failures with a remediation hint", async () => {
+    const serverRoot = await createTempDir("paperclip-desktop-port-");
+    const userDataDir = await createTempDir("paperclip-desktop-userdata-");
+
+    await writeServerEntry(
+      serverRoot,
+      `
+        console.error("Error: listen EADDRINUSE: address already in use 127.0.0.1:4383");
+        setTimeout(() => process.exit(1), 50);
+      `,
+    );
+
+    const error = await startManagedServer({
+      userDataPath: userDataDir,
+      serverRoot,
+      preferredPort: 4383,
+      startupTimeoutMs: 1_000,
+    }).catch((caught: unknown) => caught);
+
+    expect(error).toBeInstanceOf(ManagedServerStartupError);
+    expect(error).toMatchObject({
+      failureKind: "port_in_use",
+      reason: expect.stringMatching(/exited before it became healthy/),
+    });
+  });
 });
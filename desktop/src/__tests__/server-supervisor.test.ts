@@ -0,0 +1,182 @@
+import { describe, expect, test, vi } from "vitest";
+import type { ManagedServerExitInfo, ManagedServerHandle } from "../runtime/server-runtime.js";
+import { computeRestartDelayMs, startSupervisedServer } from "../runtime/server-supervisor.js";
+
+function createFakeHandle(apiUrl: string) {
+  let resolveExit: (exitInfo: ManagedServerExitInfo) => void = () => undefined;
+  const whenExit = new Promise<ManagedServerExitInfo>((resolve) => {
+    resolveExit = resolve;
+  });
+  const handle: ManagedServerHandle = {
+    apiUrl,
+    port: Number(new URL(apiUrl).port),
+    pid: null,
+    startedAt: 0,
+    health: { status: "ok", version: null, deploymentMode: null, features: {} },
+    orphanCleanup: { status: "none" },
+    paperclipHome: "/tmp/paperclip-home",
+    logsDir: "/tmp/paperclip-home/logs",
+    serverLogPath: "/tmp/paperclip-home/logs/server.log",
+    whenExit,
+    getRecentLogLines: () => ["boom"],
+    onLogLine: () => () => undefined,
+    stop: vi.fn(async () => {
+      resolveExit({ code: 0, signal: "SIGTERM" });
+    }),
+  };
+  return { handle, crash: (exitInfo: ManagedServerExitInfo) => resolveExit(exitInfo) };
+}
+
+describe("server-supervisor", () => {
+  test("backs off exponentially up to the cap", () => {
+    expect([1, 2, 3, 4, 5, 6].map((attempt) => computeRestartDelayMs(attempt, 1_000, 10_000))).toEqual([
+      1_000,
+      2_000,
+      4_000,
+      8_000,
+      10_000,
+      10_000,
+    ]);
+  });
+
+  test("restarts a crashed server and reports the new handle", async () => {
+    const first = createFakeHandle("http://127.0.0.1:3100");
+    const second = createFakeHandle("http://127.0.0.1:3100");
+    const start = vi.fn()
+      .mockResolvedValueOnce(first.handle)
+      .mockResolvedValueOnce(second.handle);
+    const onCrashed = vi.fn();
+    const onRestarted = vi.fn();
+
+    const supervisor = await startSupervisedServer({
+      start,
+      isAutoRestartEnabled: () => true,
+      baseDelayMs: 1,
+      onCrashed,
+      onRestarted,
+    });
+
+    first.crash({ code: null, signal: "SIGABRT" });
+    await vi.waitFor(() => {
+      expect(onRestarted).toHaveBeenCalledWith({ handle: second.handle, attempt: 1 });
+    });
+
+    expect(start).toHaveBeenLastCalledWith(first.handle);
+    expect(onCrashed).toHaveBeenCalledWith(expect.objectContaining({
+      reason: "signal SIGABRT",
+      attempt: 1,
+      willRestart: true,
+      logLines: ["boom"],
+    }));
+    expect(supervisor.getHandle()).toBe(second.handle);
+
+    await supervisor.stop();
+    expect(second.handle.stop).toHaveBeenCalled();
+  });
+
+  test("restarts on request without reporting a crash", async () => {
+    const first = createFakeHandle("http://127.0.0.1:3100");
+    const second = createFakeHandle("http://127.0.0.1:3100");
+    const start = vi.fn()
+      .mockResolvedValueOnce(first.handle)
+      .mockResolvedValueOnce(second.handle);
+    const onCrashed = vi.fn();
+    const onRestarted = vi.fn();
+
+    const supervisor = await startSupervisedServer({
+      start,
+      isAutoRestartEnabled: () => true,
+      baseDelayMs: 1,
+      onCrashed,
+      onRestarted,
+    });
+
+    await supervisor.restart();
+
+    expect(first.handle.stop).toHaveBeenCalled();
+    expect(start).toHaveBeenLastCalledWith(first.handle);
+    expect(onRestarted).toHaveBeenCalledWith({ handle: second.handle, attempt: 0 });
+    expect(onCrashed).not.toHaveBeenCalled();
+    expect(supervisor.getHandle()).toBe(second.handle);
+
+    await supervisor.stop();
+  });
+
+  test("stop waits for an in-flight restart and stops the new server", async () => {
+    const first = createFakeHandle("http://127.0.0.1:3100");
+    const second = createFakeHandle("http://127.0.0.1:3100");
+    let finishRestart: (handle: ManagedServerHandle) => void = () => undefined;
+    const start = vi.fn()
+      .mockResolvedValueOnce(first.handle)
+      .mockImplementationOnce(() => new Promise<ManagedServerHandle>((resolve) => {
+        finishRestart = resolve;
+      }));
+    const onRestarted = vi.fn();
+
+    const supervisor = await startSupervisedServer({
+      start,
+      isAutoRestartEnabled: () => true,
+      baseDelayMs: 1,
+      onRestarted,
+    });
+
+    first.crash({ code: 1, signal: null });
+    await vi.waitFor(() => {
+      expect(start).toHaveBeenCalledTimes(2);
+    });
+
+    let stopped = false;
+    const stopping = supervisor.stop().then(() => {
+      stopped = true;
+    });
+    await new Promise((resolve) => setTimeout(resolve, 20));
+    expect(stopped).toBe(false);
+
+    finishRestart(second.handle);
+    await stopping;
+
+    expect(second.handle.stop).toHaveBeenCalled();
+    expect(onRestarted).not.toHaveBeenCalled();
+    expect(supervisor.getHandle()).toBeNull();
+  });
+
+  test("stop cancels a pending restart backoff", async () => {
+    const first = createFakeHandle("http://127.0.0.1:3100");
+    const start = vi.fn().mockResolvedValueOnce(first.handle);
+    const onCrashed = vi.fn();
+
+    const supervisor = await startSupervisedServer({
+      start,
+      isAutoRestartEnabled: () => true,
+      baseDelayMs: 60_000,
+      onCrashed,
+    });
+
+    first.crash({ code: 1, signal: null });
+    await vi.waitFor(() => {
+      expect(onCrashed).toHaveBeenCalled();
+    });
+
+    await supervisor.stop();
+    expect(start).toHaveBeenCalledTimes(1);
+  });
+
+  test("gives up when auto-restart is disabled", async () => {
+    const first = createFakeHandle("http://127.0.0.1:3100");
+    const start = vi.fn().mockResolvedValueOnce(first.handle);
+    const onGaveUp = vi.fn();
+
+    const supervisor = await startSupervisedServer({
+      start,
+      isAutoRestartEnabled: () => false,
+      onGaveUp,
+    });
+
+    first.crash({ code: 1, signal: null });
+    await vi.waitFor(() => {
+      expect(onGaveUp).toHaveBeenCalledWith(expect.objectContaining({ reason: "exit code 1", willRestart: false }));
+    });
+    expect(start).toHaveBeenCalledTimes(1);
+    expect(supervisor.getHandle()).toBeNull();
+  });
+});
@@ -1,8 +1,17 @@
-import { app, BrowserWindow, Menu, dialog, ipcMain, shell, type OpenDialogOptions } from "electron";
+import {
+  app,
+  BrowserWindow,
+  Menu,
+  dialog,
+  ipcMain,
+  shell,
+  type IpcMainInvokeEvent,
+  type OpenDialogOptions,
+} from "electron";
 import path from "node:path";
 import { existsSync, mkdirSync } from "node:fs";
 import {
-  formatExitReason,
+  ManagedServerStartupError,
   resolveDesktopPaperclipHome,
   resolveDesktopServerLogPath,
   resolveDesktopServerRoot,
@@ -19,6 +28,22 @@ import {
   resolveDesktopLogPath,
   type DesktopLogger,
 } from "./runtime/desktop-log.js";
+import {
+  startSupervisedServer,
+  type ManagedServerSupervisor,
+} from "./runtime/server-supervisor.js";
+import { readProcessStats, type ManagedProcessStats } from "./runtime/process-stats.js";
+import {
+  createDesktopEventBus,
+  isReplayableDesktopEvent,
+  toDesktopEventChannel,
+  type DesktopEventType,
+} from "./runtime/desktop-events.js";
+import {
+  createDesktopEventJournal,
+  resolveDesktopEventJournalPath,
+  type DesktopEventJournal,
+} from "./runtime/desktop-event-journal.js";
 import { attachRendererDiagnostics } from "./runtime/renderer-diagnostics.js";
 import {
   createDesktopUpdater,
@@ -30,17 +55,34 @@ import {
   saveWindowState,
   type SavedWindowState,
 } from "./runtime/window-state.js";
+import {
+  loadDesktopSettings,
+  parseDesktopSettings,
+  pickRendererSettingsPatch,
+  resolveDesktopSettingsPath,
+  saveDesktopSettings,
+  type DesktopSettings,
+} from "./runtime/desktop-settings.js";
 import { createApplicationMenuTemplate, readChosenDirectory } from "./runtime/application-menu.js";
 import { resolveDirectoryPickerDefaultPath } from "./runtime/directory-picker.js";
-import { renderSplashHtml, renderStartupErrorHtml, toDataUrl } from "./window-html.js";
+import {
+  renderSplashHtml,
+  renderStartupErrorHtml,
+  SPLASH_STATUS_ELEMENT_ID,
+  toDataUrl,
+} from "./window-html.js";
+
+const CRASH_EVENT_LOG_LINES = 20;
 
 let mainWindow: BrowserWindow | null = null;
 let splashWindow: BrowserWindow | null = null;
-let serverHandle: ManagedServerHandle | null = null;
+let serverSupervisor: ManagedServerSupervisor | null = null;
 let desktopLogger: DesktopLogger | null = null;
 let desktopUpdater: DesktopUpdaterHandle | null = null;
+let desktopEventJournal: DesktopEventJournal | null = null;
 let buildMetadata: DesktopBuildMetadata | null = null;
 let isQuitting = false;
+const desktopEvents = createDesktopEventBus();
 let updaterStarted = false;
 
 function resolveDesktopDevUrl(): string | null {
@@ -56,6 +98,10 @@ function getPreloadPath(): string {
   return path.resolve(__dirname, "preload.js");
 }
 
+function getServerHandle(): ManagedServerHandle | null {
+  return serverSupervisor?.getHandle() ?? null;
+}
+
 function getDesktopLogPath(): string {
   return resolveDesktopLogPath(app.getPath("userData"));
 }
@@ -66,6 +112,20 @@ function getBuildMetadata(): DesktopBuildMetadata | null {
   return buildMetadata;
 }
 
+function getDesktopSettingsPath(): string {
+  return resolveDesktopSettingsPath(app.getPath("userData"));
+}
+
+function getDesktopSettings(): DesktopSettings {
+  return loadDesktopSettings(getDesktopSettingsPath());
+}
+
+function updateDesktopSettings(patch: unknown): DesktopSettings {
+  const next = parseDesktopSettings(patch, getDesktopSettings());
+  saveDesktopSettings(getDesktopSettingsPath(), next);
+  return next;
+}
+
 function getWindowStatePath(): string {
   return path.resolve(app.getPath("userData"), "window-state.json");
 }
@@ -157,6 +217,12 @@ function isSameOriginUrl(rawUrl: string, allowedOrigin: string): boolean {
   }
 }
 
+function isAppFrameSender(event: IpcMainInvokeEvent): boolean {
+  const baseUrl = resolveDesktopDevUrl() ?? getServerHandle()?.apiUrl;
+  const frameUrl = event.senderFrame?.url;
+  return Boolean(baseUrl && frameUrl && isSameOriginUrl(frameUrl, new URL(baseUrl).origin));
+}
+
 function wireExternalNavigation(window: BrowserWindow, baseUrl: string) {
   const allowedOrigin = new URL(baseUrl).origin;
   window.webContents.setWindowOpenHandler(({ url }) => {
@@ -202,8 +268,9 @@ async function createMainWindow(baseUrl: string) {
       log: desktopLogger,
     });
   }
-  mainWindow.on("closed", () => {
-    mainWindow = null;
+  const createdWindow = mainWindow;
+  createdWindow.on("closed", () => {
+    if (mainWindow === createdWindow) mainWindow = null;
   });
   mainWindow.once("ready-to-show", () => {
     mainWindow?.show();
@@ -217,6 +284,7 @@ async function createMainWindow(baseUrl: string) {
 }
 
 function rebuildApplicationMenu() {
+  const settings = getDesktopSettings();
   const updateMenuState = desktopUpdater?.getMenuState() ?? {
     enabled: false,
     label: "Check for Updates…",
@@ -225,15 +293,58 @@ function rebuildApplicationMenu() {
     appName: app.name,
     platform: process.platform,
     updateMenuState,
+    serverDebugEnabled: settings.serverDebug,
+    serverAutoRestartEnabled: settings.serverAutoRestart,
     onCheckForUpdates: () => {
       void desktopUpdater?.performMenuAction();
     },
+    onToggleServerDebug: (enabled) => {
+      void setServerDebugMode(enabled);
+    },
+    onToggleServerAutoRestart: (enabled) => {
+      updateDesktopSettings({ serverAutoRestart: enabled });
+      desktopLogger?.info(`Server auto-restart ${enabled ? "enabled" : "disabled"}.`);
+      rebuildApplicationMenu();
+    },
   });
 
   Menu.setApplicationMenu(Menu.buildFromTemplate(template));
 }
 
-async function showStartupError(reason: string, logLines: string[]) {
+async function setServerDebugMode(enabled: boolean) {
+  updateDesktopSettings({ serverDebug: enabled });
+  desktopLogger?.info(`Server debug mode ${enabled ? "enabled" : "disabled"}; takes effect on the next server start.`);
+  rebuildApplicationMenu();
+  if (!getServerHandle()) return;
+
+  const ownerWindow = mainWindow ?? splashWindow;
+  const options = {
+    type: "info" as const,
+    buttons: ["Restart Server", "Later"],
+    defaultId: 0,
+    cancelId: 1,
+    message: `Server debug mode ${enabled ? "enabled" : "disabled"}`,
+    detail: "Restart the local server to apply the new logging settings.",
+  };
+  const result = ownerWindow
+    ? await dialog.showMessageBox(ownerWindow, options)
+    : await dialog.showMessageBox(options);
+  if (result.response === 0) {
+    desktopLogger?.info("Restarting managed server to apply debug mode.");
+    await serverSupervisor?.restart();
+  }
+}
+
+function setSplashStatus(message: string) {
+  if (!splashWindow || splashWindow.isDestroyed()) return;
+  const script = `(() => {
+    const element = document.getElementById(${JSON.stringify(SPLASH_STATUS_ELEMENT_ID)});
+    if (element) element.textContent = ${JSON.stringify(message)};
+  })()`;
+  void splashWindow.webContents.executeJavaScript(script).catch(() => undefined);
+}
+
+async function showStartupError(reason: string, logLines: string[], remediation?: string) {
   if (!splashWindow || splashWindow.isDestroyed()) {
     await createSplashWindow();
   }
@@ -242,20 +353,21 @@ async function showStartupError(reason: string, logLines: string[]) {
       renderStartupErrorHtml({
         reason,
         logLines,
+        remediation,
       }),
     ),
   );
 }
 
 function getLogsPathForActions(): string {
-  const serverLogPath = serverHandle?.serverLogPath
+  const serverLogPath = getServerHandle()?.serverLogPath
     ?? resolveDesktopServerLogPath(resolveDesktopPaperclipHome(app.getPath("userData")));
   if (existsSync(serverLogPath)) return serverLogPath;
   return getDesktopLogPath();
 }
 
 function getPaperclipHomeForActions(): string {
-  return serverHandle?.paperclipHome ?? resolveDesktopPaperclipHome(app.getPath("userData"));
+  return getServerHandle()?.paperclipHome ?? resolveDesktopPaperclipHome(app.getPath("userData"));
 }
 
 function getDirectoryPickerDefaultPath(): string {
@@ -302,37 +414,138 @@ async function revealPath(targetPath: string) {
   shell.showItemInFolder(path.resolve(normalized));
 }
 
+function getInstanceInfo() {
+  const serverHandle = getServerHandle();
+  if (!serverHandle) return null;
+  return {
+    apiUrl: serverHandle.apiUrl,
+    port: serverHandle.port,
+    paperclipHome: serverHandle.paperclipHome,
+    health: serverHandle.health,
+  };
+}
+
+async function getProcessStats(): Promise<ManagedProcessStats[]> {
+  const serverHandle = getServerHandle();
+  if (!serverHandle?.pid) return [];
+  return [
+    await readProcessStats({
+      label: "server",
+      pid: serverHandle.pid,
+      startedAt: serverHandle.startedAt,
+    }),
+  ];
+}
+
+async function startDesktopServer(previous: ManagedServerHandle | null): Promise<ManagedServerHandle> {
+  const settings = getDesktopSettings();
+  const handle = await startManagedServer({
+    userDataPath: app.getPath("userData"),
+    serverRoot: resolveDesktopServerRoot({
+      isPackaged: app.isPackaged,
+      appPath: app.getAppPath(),
+      resourcesPath: process.resourcesPath,
+    }),
+    preferredPort: previous?.port,
+    portRange: settings.serverPortRange,
+    stopTimeoutMs: settings.serverStopGracePeriodMs,
+    startupTimeoutMs: settings.serverStartupTimeoutMs,
+    healthPath: settings.serverHealthPath,
+    maxOldSpaceMb: settings.serverMaxOldSpaceMb,
+    env: settings.serverEnv,
+    onStartupProgress: (elapsedMs) => {
+      const seconds = Math.round(elapsedMs / 1000);
+      desktopLogger?.warn(`Managed server still starting after ${seconds}s.`);
+      setSplashStatus(`Still starting (${seconds}s)… First launch on a cold machine can take a minute.`);
+      desktopEvents.emit("server-starting", { elapsedMs });
+    },
+    logLevel: settings.serverLogLevel,
+    debug: settings.serverDebug,
+  });
+  if (handle.orphanCleanup.status === "killed") {
+    const { lock } = handle.orphanCleanup;
+    desktopLogger?.warn(`Stopped orphaned Paperclip server (pid ${lock.pid}) left by a previous session.`);
+    desktopEvents.emit("server-orphan-stopped", {
+      pid: lock.pid,
+      port: lock.port,
+      startedAt: new Date(lock.startedAt).toISOString(),
+    });
+  }
+  handle.onLogLine((entry) => {
+    desktopEvents.emit("server-log", entry);
+  });
+  return handle;
+}
+
 async function bootPackagedRuntime() {
   await createSplashWindow();
 
   try {
     desktopLogger?.info("Booting packaged Paperclip desktop runtime.");
-    serverHandle = await startManagedServer({
-      userDataPath: app.getPath("userData"),
-      serverRoot: resolveDesktopServerRoot({
-        isPackaged: app.isPackaged,
-        appPath: app.getAppPath(),
-        resourcesPath: process.resourcesPath,
-      }),
-    });
-    void serverHandle.whenExit.then(async (exitInfo) => {
-      if (isQuitting) return;
-      const logLines = serverHandle?.getRecentLogLines() ?? [];
-      desktopLogger?.error(`Managed server exited unexpectedly (${formatExitReason(exitInfo)}).`);
-      await showStartupError(
-        `The local Paperclip server stopped unexpectedly (${formatExitReason(exitInfo)}).`,
-        logLines,
-      );
-      mainWindow?.close();
-      mainWindow = null;
+    serverSupervisor = await startSupervisedServer({
+      start: startDesktopServer,
+      isAutoRestartEnabled: () => !isQuitting && getDesktopSettings().serverAutoRestart,
+      onCrashed: (event) => {
+        desktopLogger?.error(
+          event.willRestart
+            ? `Managed server exited unexpectedly (${event.reason}); restart attempt ${event.attempt} in ${event.delayMs}ms.`
+            : `Managed server exited unexpectedly (${event.reason}).`,
+        );
+        desktopEvents.emit("server-crashed", {
+          reason: event.reason,
+          exitCode: event.exitInfo?.code ?? null,
+          signal: event.exitInfo?.signal ?? null,
+          logLines: event.logLines.slice(-CRASH_EVENT_LOG_LINES),
+          attempt: event.attempt,
+          willRestart: event.willRestart,
+          delayMs: event.delayMs,
+        });
+      },
+      onRestarted: ({ handle, attempt }) => {
+        desktopLogger?.info(
+          attempt > 0
+            ? `Managed server restarted at ${handle.apiUrl} after ${attempt} attempt(s).`
+            : `Managed server restarted at ${handle.apiUrl}.`,
+        );
+        desktopEvents.emit("server-restarted", { apiUrl: handle.apiUrl, attempt });
+        if (
+          mainWindow
+          && !mainWindow.isDestroyed()
+          && isSameOriginUrl(mainWindow.webContents.getURL(), new URL(handle.apiUrl).origin)
+        ) {
+          mainWindow.webContents.reload();
+          return;
+        }
+        const previousWindow = mainWindow;
+        void createMainWindow(handle.apiUrl).finally(() => {
+          if (previousWindow && !previousWindow.isDestroyed()) previousWindow.destroy();
+        });
+      },
+      onGaveUp: (event) => {
+        if (isQuitting) return;
+        void showStartupError(
+          `The local Paperclip server stopped unexpectedly (${event.reason}).`,
+          event.logLines,
+        ).then(() => {
+          mainWindow?.close();
+          mainWindow = null;
+        });
+      },
     });
+    const serverHandle = getServerHandle();
+    if (!serverHandle) return;
     await createMainWindow(serverHandle.apiUrl);
   } catch (error) {
-    const rawMessage = error instanceof Error ? error.message : String(error);
-    const [reason, logTail] = rawMessage.split("\n\nRecent server logs:\n");
-    const logLines = logTail ? logTail.split("\n").filter(Boolean) : serverHandle?.getRecentLogLines() ?? [];
+    if (error instanceof ManagedServerStartupError) {
+      desktopLogger?.error(
+        `Desktop startup failed (${error.failureKind}): ${error.reason} Command: ${error.commandLine}`,
+      );
+      await showStartupError(error.reason, error.logLines, error.remediation);
+      return;
+    }
+    const reason = error instanceof Error ? error.message : String(error);
     desktopLogger?.error(`Desktop startup failed: ${reason}`);
-    await showStartupError(reason, logLines);
+    await showStartupError(reason, getServerHandle()?.getRecentLogLines() ?? []);
   }
 }
 
@@ -346,10 +559,10 @@ async function bootDesktopShell() {
 }
 
 async function stopManagedServerIfNeeded() {
-  const handle = serverHandle;
-  serverHandle = null;
-  if (handle) {
-    await handle.stop();
+  const supervisor = serverSupervisor;
+  serverSupervisor = null;
+  if (supervisor) {
+    await supervisor.stop();
   }
 }
 
@@ -382,6 +595,23 @@ app.once("before-quit", () => {
 
 app.whenReady().then(async () => {
   desktopLogger = createDesktopLogger(getDesktopLogPath());
+  desktopEventJournal = createDesktopEventJournal({
+    filePath: resolveDesktopEventJournalPath(app.getPath("userData")),
+  });
+  desktopEvents.resumeFrom(desktopEventJournal.lastSequence());
+  desktopEvents.subscribe((event) => {
+    if (isReplayableDesktopEvent(event.type)) {
+      try {
+        desktopEventJournal?.append(event);
+      } catch (error) {
+        const message = error instanceof Error ? error.message : String(error);
+        desktopLogger?.warn(`Could not append to desktop event journal: ${message}`);
+      }
+    }
+    if (mainWindow && !mainWindow.isDestroyed()) {
+      mainWindow.webContents.send(toDesktopEventChannel(event.type), event);
+    }
+  });
   const metadata = getBuildMetadata();
   if (metadata) {
     desktopLogger.info(`Desktop build ${metadata.version} (${metadata.channel}) loaded.`);
@@ -416,6 +646,35 @@ app.whenReady().then(async () => {
   ipcMain.handle("paperclip-desktop:reveal-path", async (_event, targetPath: string) => {
     await revealPath(targetPath);
   });
+  ipcMain.handle("paperclip-desktop:get-instance-info", async () => {
+    return getInstanceInfo();
+  });
+  ipcMain.handle(
+    "paperclip-desktop:replay-events",
+    async (_event, filter?: { types?: DesktopEventType[]; afterSequence?: number }) => {
+      return desktopEvents.replay(filter ?? {});
+    },
+  );
+  ipcMain.handle("paperclip-desktop:read-event-journal", async (_event, since?: string) => {
+    return desktopEventJournal?.read({ since }) ?? [];
+  });
+  ipcMain.handle("paperclip-desktop:get-process-stats", async () => {
+    return getProcessStats();
+  });
+  ipcMain.handle("paperclip-desktop:get-server-logs", async (_event, limit?: number) => {
+    return getServerHandle()?.getRecentLogLines(typeof limit === "number" && limit > 0 ? limit : undefined) ?? [];
+  });
+  ipcMain.handle("paperclip-desktop:get-settings", async (event) => {
+    if (!isAppFrameSender(event)) throw new Error("Desktop settings are only available to the Paperclip app.");
+    return getDesktopSettings();
+  });
+  ipcMain.handle("paperclip-desktop:update-settings", async (event, patch: unknown) => {
+    if (!isAppFrameSender(event)) throw new Error("Desktop settings are only available to the Paperclip app.");
+    const settings = updateDesktopSettings(pickRendererSettingsPatch(patch));
+    desktopLogger?.info("Desktop settings updated; server settings take effect on the next server start.");
+    rebuildApplicationMenu();
+    return settings;
+  });
 
   await bootDesktopShell();
 }).catch((error) => {
@@ -426,7 +685,7 @@ app.whenReady().then(async () => {
 
 app.on("will-quit", (event) => {
   desktopUpdater?.dispose();
-  if (!serverHandle) return;
+  if (!serverSupervisor) return;
   event.preventDefault();
   void stopManagedServerIfNeeded().finally(() => {
     app.exit(0);
@@ -1,4 +1,12 @@
-import { contextBridge, ipcRenderer } from "electron";
+import { contextBridge, ipcRenderer, type IpcRendererEvent } from "electron";
+
+function subscribe<T>(channel: string, listener: (payload: T) => void): () => void {
+  const handler = (_event: IpcRendererEvent, payload: T) => listener(payload);
+  ipcRenderer.on(channel, handler);
+  return () => {
+    ipcRenderer.removeListener(channel, handler);
+  };
+}
 
 contextBridge.exposeInMainWorld("paperclipDesktop", {
   openLogs: () => ipcRenderer.invoke("paperclip-desktop:open-logs"),
@@ -6,4 +14,18 @@ contextBridge.exposeInMainWorld("paperclipDesktop", {
   reloadApp: () => ipcRenderer.invoke("paperclip-desktop:reload-app"),
   chooseDirectory: () => ipcRenderer.invoke("paperclip-desktop:choose-directory"),
   revealPath: (targetPath: string) => ipcRenderer.invoke("paperclip-desktop:reveal-path", targetPath),
+  getInstanceInfo: () => ipcRenderer.invoke("paperclip-desktop:get-instance-info"),
+  getServerLogs: (limit?: number) => ipcRenderer.invoke("paperclip-desktop:get-server-logs", limit),
+  replayEvents: (filter?: { types?: string[]; afterSequence?: number }) =>
+    ipcRenderer.invoke("paperclip-desktop:replay-events", filter),
+  readEventJournal: (since?: string) => ipcRenderer.invoke("paperclip-desktop:read-event-journal", since),
+  getProcessStats: () => ipcRenderer.invoke("paperclip-desktop:get-process-stats"),
+  getSettings: () => ipcRenderer.invoke("paperclip-desktop:get-settings"),
+  updateSettings: (patch: unknown) => ipcRenderer.invoke("paperclip-desktop:update-settings", patch),
+  onServerLog: (listener: (event: unknown) => void) => subscribe("paperclip-desktop:server-log", listener),
+  onServerStarting: (listener: (event: unknown) => void) => subscribe("paperclip-desktop:server-starting", listener),
+  onServerCrashed: (listener: (event: unknown) => void) => subscribe("paperclip-desktop:server-crashed", listener),
+  onServerRestarted: (listener: (event: unknown) => void) => subscribe("paperclip-desktop:server-restarted", listener),
+  onServerOrphanStopped: (listener: (event: unknown) => void) =>
+    subscribe("paperclip-desktop:server-orphan-stopped", listener),
 });
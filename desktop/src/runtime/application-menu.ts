@@ -9,7 +9,11 @@ interface ApplicationMenuTemplateInput {
   appName: string;
   platform: NodeJS.Platform;
   updateMenuState: DesktopUpdateMenuState;
+  serverDebugEnabled: boolean;
+  serverAutoRestartEnabled: boolean;
   onCheckForUpdates: () => void;
+  onToggleServerDebug: (enabled: boolean) => void;
+  onToggleServerAutoRestart: (enabled: boolean) => void;
 }
 
 export function createApplicationMenuTemplate(input: ApplicationMenuTemplateInput): MenuItemConstructorOptions[] {
@@ -21,6 +25,24 @@ export function createApplicationMenuTemplate(input: ApplicationMenuTemplateInpu
       input.onCheckForUpdates();
     },
   };
+  const serverDebugMenuItem: MenuItemConstructorOptions = {
+    id: "paperclip-server-debug-mode",
+    label: "Start Server in Debug Mode",
+    type: "checkbox",
+    checked: input.serverDebugEnabled,
+    click: (menuItem) => {
+      input.onToggleServerDebug(menuItem.checked);
+    },
+  };
+  const serverAutoRestartMenuItem: MenuItemConstructorOptions = {
+    id: "paperclip-server-auto-restart",
+    label: "Restart Server Automatically",
+    type: "checkbox",
+    checked: input.serverAutoRestartEnabled,
+    click: (menuItem) => {
+      input.onToggleServerAutoRestart(menuItem.checked);
+    },
+  };
 
   if (input.platform === "darwin") {
     return [
@@ -30,6 +52,8 @@ export function createApplicationMenuTemplate(input: ApplicationMenuTemplateInpu
           { role: "about" },
           { type: "separator" },
           updateMenuItem,
+          serverDebugMenuItem,
+          serverAutoRestartMenuItem,
           { type: "separator" },
           { role: "services" },
           { type: "separator" },
@@ -63,6 +87,8 @@ export function createApplicationMenuTemplate(input: ApplicationMenuTemplateInpu
       label: "Paperclip",
       submenu: [
         updateMenuItem,
+        serverDebugMenuItem,
+        serverAutoRestartMenuItem,
         { type: "separator" },
         { role: "quit" },
       ],
@@ -0,0 +1,61 @@
+import { appendFileSync, existsSync, mkdirSync, readFileSync, writeFileSync } from "node:fs";
+import path from "node:path";
+import type { DesktopEvent } from "./desktop-events.js";
+
+const DEFAULT_MAX_ENTRIES = 500;
+
+export function resolveDesktopEventJournalPath(userDataPath: string): string {
+  return path.resolve(userDataPath, "desktop-events.jsonl");
+}
+
+function readJournalEntries(filePath: string): DesktopEvent[] {
+  if (!existsSync(filePath)) return [];
+  const entries: DesktopEvent[] = [];
+  for (const line of readFileSync(filePath, "utf8").split("\n")) {
+    if (!line.trim()) continue;
+    try {
+      entries.push(JSON.parse(line) as DesktopEvent);
+    } catch {
+      continue;
+    }
+  }
+  return entries;
+}
+
+export function createDesktopEventJournal(input: { filePath: string; maxEntries?: number }) {
+  const maxEntries = input.maxEntries ?? DEFAULT_MAX_ENTRIES;
+  const existing = readJournalEntries(input.filePath);
+  let entryCount = existing.length;
+  let lastSequence = existing.reduce(
+    (max, entry) => (typeof entry.sequence === "number" ? Math.max(max, entry.sequence) : max),
+    0,
+  );
+
+  const compact = () => {
+    const retained = readJournalEntries(input.filePath).slice(-maxEntries);
+    writeFileSync(input.filePath, retained.map((entry) => `${JSON.stringify(entry)}\n`).join(""));
+    entryCount = retained.length;
+  };
+
+  return {
+    filePath: input.filePath,
+    append(event: DesktopEvent) {
+      mkdirSync(path.dirname(input.filePath), { recursive: true });
+      appendFileSync(input.filePath, `${JSON.stringify(event)}\n`);
+      entryCount += 1;
+      lastSequence = Math.max(lastSequence, event.sequence);
+      if (entryCount >= maxEntries * 2) compact();
+    },
+    lastSequence(): number {
+      return lastSequence;
+    },
+    read(filter: { since?: string } = {}): DesktopEvent[] {
+      const sinceMs = filter.since ? Date.parse(filter.since) : Number.NaN;
+      return readJournalEntries(input.filePath)
+        .slice(-maxEntries)
+        .filter((entry) => Number.isNaN(sinceMs) || Date.parse(entry.at) > sinceMs);
+    },
+  };
+}
+
+export type DesktopEventJournal = ReturnType<typeof createDesktopEventJournal>;
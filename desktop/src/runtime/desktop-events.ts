@@ -0,0 +1,79 @@
+import type { ManagedServerLogLine } from "./server-runtime.js";
+
+const DEFAULT_REPLAY_LIMIT = 50;
+
+export type DesktopEventMap = {
+  "server-log": ManagedServerLogLine;
+  "server-starting": { elapsedMs: number };
+  "server-crashed": {
+    reason: string;
+    exitCode: number | null;
+    signal: string | null;
+    logLines: string[];
+    attempt: number;
+    willRestart: boolean;
+    delayMs: number;
+  };
+  "server-restarted": { apiUrl: string; attempt: number };
+  "server-orphan-stopped": { pid: number; port: number; startedAt: string };
+};
+
+export type DesktopEventType = keyof DesktopEventMap;
+
+export type DesktopEvent = {
+  [Type in DesktopEventType]: {
+    sequence: number;
+    type: Type;
+    at: string;
+    payload: DesktopEventMap[Type];
+  };
+}[DesktopEventType];
+
+// Log lines are high-volume and already queryable through getRecentLogLines, so they are not replayed.
+const NON_REPLAYABLE_TYPES = new Set<DesktopEventType>(["server-log"]);
+
+export function isReplayableDesktopEvent(type: DesktopEventType): boolean {
+  return !NON_REPLAYABLE_TYPES.has(type);
+}
+
+export function toDesktopEventChannel(type: DesktopEventType): string {
+  return `paperclip-desktop:${type}`;
+}
+
+export function createDesktopEventBus(input: { replayLimit?: number; now?: () => Date } = {}) {
+  const replayLimit = input.replayLimit ?? DEFAULT_REPLAY_LIMIT;
+  const now = input.now ?? (() => new Date());
+  const history: DesktopEvent[] = [];
+  const listeners = new Set<(event: DesktopEvent) => void>();
+  let sequence = 0;
+
+  return {
+    emit<Type extends DesktopEventType>(type: Type, payload: DesktopEventMap[Type]): DesktopEvent {
+      sequence += 1;
+      const event = { sequence, type, at: now().toISOString(), payload } as DesktopEvent;
+      if (isReplayableDesktopEvent(type)) {
+        history.push(event);
+        if (history.length > replayLimit) history.splice(0, history.length - replayLimit);
+      }
+      for (const listener of listeners) listener(event);
+      return event;
+    },
+    // Continue numbering after a previous session so journaled sequences stay unique.
+    resumeFrom(lastSequence: number) {
+      sequence = Math.max(sequence, lastSequence);
+    },
+    replay(filter: { types?: DesktopEventType[]; afterSequence?: number } = {}): DesktopEvent[] {
+      return history.filter((event) =>
+        (!filter.types || filter.types.includes(event.type))
+        && (filter.afterSequence === undefined || event.sequence > filter.afterSequence));
+    },
+    subscribe(listener: (event: DesktopEvent) => void): () => void {
+      listeners.add(listener);
+      return () => {
+        listeners.delete(listener);
+      };
+    },
+  };
+}
+
+export type DesktopEventBus = ReturnType<typeof createDesktopEventBus>;
@@ -0,0 +1,119 @@
+import { mkdirSync, readFileSync, writeFileSync } from "node:fs";
+import path from "node:path";
+
+export const DESKTOP_SERVER_LOG_LEVELS = ["trace", "debug", "info", "warn", "error"] as const;
+
+export type DesktopServerLogLevel = (typeof DESKTOP_SERVER_LOG_LEVELS)[number];
+
+export type DesktopPortRange = { start: number; end: number };
+
+export type DesktopSettings = {
+  serverLogLevel: DesktopServerLogLevel;
+  serverDebug: boolean;
+  serverAutoRestart: boolean;
+  serverStopGracePeriodMs: number;
+  serverStartupTimeoutMs: number;
+  serverHealthPath: string;
+  serverMaxOldSpaceMb: number | null;
+  serverPortRange: DesktopPortRange | null;
+  serverEnv: Record<string, string>;
+};
+
+export const DEFAULT_DESKTOP_SETTINGS: DesktopSettings = {
+  serverLogLevel: "debug",
+  serverDebug: false,
+  serverAutoRestart: true,
+  serverStopGracePeriodMs: 10_000,
+  serverStartupTimeoutMs: 90_000,
+  serverHealthPath: "/api/health",
+  serverMaxOldSpaceMb: null,
+  serverPortRange: null,
+  serverEnv: {},
+};
+
+// Env, node flags and ports change how the server process is launched, so only desktop-settings.json sets them.
+const RENDERER_WRITABLE_SETTINGS = [
+  "serverLogLevel",
+  "serverDebug",
+  "serverAutoRestart",
+  "serverStopGracePeriodMs",
+  "serverStartupTimeoutMs",
+  "serverHealthPath",
+] as const satisfies ReadonlyArray<keyof DesktopSettings>;
+
+export type RendererDesktopSettingsPatch = Partial<Pick<DesktopSettings, (typeof RENDERER_WRITABLE_SETTINGS)[number]>>;
+
+function readPositiveInteger(value: unknown, fallback: number): number {
+  return typeof value === "number" && Number.isInteger(value) && value > 0 ? value : fallback;
+}
+
+function readHealthPath(value: unknown, fallback: string): string {
+  return typeof value === "string" && value.startsWith("/") && !value.startsWith("//") ? value : fallback;
+}
+
+function readPortRange(value: unknown, fallback: DesktopPortRange | null): DesktopPortRange | null {
+  if (value === null) return null;
+  if (!value || typeof value !== "object") return fallback;
+  const { start, end } = value as Record<string, unknown>;
+  const isPort = (port: unknown): port is number =>
+    typeof port === "number" && Number.isInteger(port) && port > 0 && port <= 65_535;
+  return isPort(start) && isPort(end) && start <= end ? { start, end } : fallback;
+}
+
+function readStringMap(value: unknown): Record<string, string> {
+  if (!value || typeof value !== "object" || Array.isArray(value)) return {};
+  const entries = Object.entries(value as Record<string, unknown>)
+    .filter((entry): entry is [string, string] => entry[0].trim().length > 0 && typeof entry[1] === "string");
+  return Object.fromEntries(entries);
+}
+
+function isServerLogLevel(value: unknown): value is DesktopServerLogLevel {
+  return typeof value === "string" && (DESKTOP_SERVER_LOG_LEVELS as readonly string[]).includes(value);
+}
+
+export function resolveDesktopSettingsPath(userDataPath: string): string {
+  return path.resolve(userDataPath, "desktop-settings.json");
+}
+
+// Fields that are missing or invalid keep their value from `fallback`.
+export function parseDesktopSettings(value: unknown, fallback: DesktopSettings = DEFAULT_DESKTOP_SETTINGS): DesktopSettings {
+  if (!value || typeof value !== "object" || Array.isArray(value)) return fallback;
+  const parsed = value as Partial<Record<keyof DesktopSettings, unknown>>;
+
+  return {
+    serverLogLevel: isServerLogLevel(parsed.serverLogLevel) ? parsed.serverLogLevel : fallback.serverLogLevel,
+    serverDebug: typeof parsed.serverDebug === "boolean" ? parsed.serverDebug : fallback.serverDebug,
+    serverAutoRestart: typeof parsed.serverAutoRestart === "boolean"
+      ? parsed.serverAutoRestart
+      : fallback.serverAutoRestart,
+    serverStopGracePeriodMs: readPositiveInteger(parsed.serverStopGracePeriodMs, fallback.serverStopGracePeriodMs),
+    serverStartupTimeoutMs: readPositiveInteger(parsed.serverStartupTimeoutMs, fallback.serverStartupTimeoutMs),
+    serverHealthPath: readHealthPath(parsed.serverHealthPath, fallback.serverHealthPath),
+    serverMaxOldSpaceMb: parsed.serverMaxOldSpaceMb === null
+      ? null
+      : readPositiveInteger(parsed.serverMaxOldSpaceMb, fallback.serverMaxOldSpaceMb ?? 0) || null,
+    serverPortRange: readPortRange(parsed.serverPortRange, fallback.serverPortRange),
+    serverEnv: parsed.serverEnv === undefined ? fallback.serverEnv : readStringMap(parsed.serverEnv),
+  };
+}
+
+export function pickRendererSettingsPatch(patch: unknown): RendererDesktopSettingsPatch {
+  if (!patch || typeof patch !== "object" || Array.isArray(patch)) return {};
+  const record = patch as Record<string, unknown>;
+  return Object.fromEntries(
+    RENDERER_WRITABLE_SETTINGS.filter((key) => key in record).map((key) => [key, record[key]]),
+  ) as RendererDesktopSettingsPatch;
+}
+
+export function loadDesktopSettings(filePath: string): DesktopSettings {
+  try {
+    return parseDesktopSettings(JSON.parse(readFileSync(filePath, "utf8")));
+  } catch {
+    return DEFAULT_DESKTOP_SETTINGS;
+  }
+}
+
+export function saveDesktopSettings(filePath: string, settings: DesktopSettings): void {
+  mkdirSync(path.dirname(filePath), { recursive: true });
+  writeFileSync(filePath, JSON.stringify(settings, null, 2));
+}
@@ -0,0 +1,55 @@
+import type { ManagedServerExitInfo } from "./server-runtime.js";
+
+export type StartupFailureKind =
+  | "port_in_use"
+  | "out_of_memory"
+  | "missing_module"
+  | "permission_denied"
+  | "database_unavailable"
+  | "startup_timeout"
+  | "server_crash";
+
+export type StartupFailureAnalysis = {
+  failureKind: StartupFailureKind;
+  remediation: string;
+};
+
+const REMEDIATION: Record<StartupFailureKind, string> = {
+  port_in_use: "Another process is already listening on the server port. Quit the other Paperclip instance or free the port, then retry.",
+  out_of_memory: "The local server ran out of memory. Close other heavy applications or raise the server memory limit, then retry.",
+  missing_module: "The bundled server runtime is incomplete. Reinstall Paperclip or rebuild the desktop package.",
+  permission_denied: "The server could not access its data folder. Check permissions on the Paperclip data folder.",
+  database_unavailable: "The embedded database could not be opened. Make sure no other Paperclip process is using the data folder.",
+  startup_timeout: "The server did not become healthy in time. Retry, or open the logs to see where startup stalled.",
+  server_crash: "The server exited during startup. Open the logs for the full error output.",
+};
+
+const LOG_PATTERNS: Array<{ kind: StartupFailureKind; pattern: RegExp }> = [
+  { kind: "port_in_use", pattern: /EADDRINUSE|address already in use/i },
+  { kind: "out_of_memory", pattern: /heap out of memory|ERR_WORKER_OUT_OF_MEMORY|Allocation failed/i },
+  { kind: "missing_module", pattern: /Cannot find module|ERR_MODULE_NOT_FOUND/ },
+  { kind: "permission_denied", pattern: /EACCES|EPERM|permission denied/i },
+  // Messages from formatEmbeddedPostgresError and the server's embedded Postgres startup logging.
+  {
+    kind: "database_unavailable",
+    pattern:
+      /Failed to (initialize|start) embedded PostgreSQL|Embedded PostgreSQL (failed|bootstrap could not)|Recent embedded Postgres logs:/,
+  },
+];
+
+export function analyzeStartupFailure(input: {
+  exitInfo: ManagedServerExitInfo | null;
+  logLines: string[];
+}): StartupFailureAnalysis {
+  // A server that is still running only failed to answer health checks; earlier log noise does not explain that.
+  if (!input.exitInfo) {
+    return { failureKind: "startup_timeout", remediation: REMEDIATION.startup_timeout };
+  }
+
+  // V8 heap exhaustion aborts with SIGABRT/134, but so do other native crashes, so OOM is only read from the logs.
+  const logText = input.logLines.join("\n");
+  const matched = LOG_PATTERNS.find(({ pattern }) => pattern.test(logText));
+
+  const failureKind = matched?.kind ?? "server_crash";
+  return { failureKind, remediation: REMEDIATION[failureKind] };
+}
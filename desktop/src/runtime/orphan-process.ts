@@ -0,0 +1,118 @@
+import { execFile } from "node:child_process";
+import { mkdirSync, readFileSync, rmSync, writeFileSync } from "node:fs";
+import path from "node:path";
+import { promisify } from "node:util";
+import { signalProcessGroup } from "./process-group.js";
+
+const execFileAsync = promisify(execFile);
+const ORPHAN_POLL_INTERVAL_MS = 100;
+const ORPHAN_STOP_TIMEOUT_MS = 5_000;
+
+export type ManagedServerLock = {
+  pid: number;
+  port: number;
+  serverEntry: string;
+  startedAt: number;
+};
+
+export type OrphanCleanupResult =
+  | { status: "none" }
+  | { status: "stale"; lock: ManagedServerLock }
+  | { status: "foreign"; lock: ManagedServerLock }
+  | { status: "killed"; lock: ManagedServerLock };
+
+function delay(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+export function resolveServerLockPath(instanceDir: string): string {
+  return path.resolve(instanceDir, "desktop-server.lock.json");
+}
+
+export function writeServerLock(lockPath: string, lock: ManagedServerLock): void {
+  mkdirSync(path.dirname(lockPath), { recursive: true });
+  writeFileSync(lockPath, JSON.stringify(lock, null, 2));
+}
+
+export function readServerLock(lockPath: string): ManagedServerLock | null {
+  try {
+    const parsed = JSON.parse(readFileSync(lockPath, "utf8")) as Partial<ManagedServerLock>;
+    if (
+      Number.isInteger(parsed.pid)
+      && Number.isInteger(parsed.port)
+      && typeof parsed.serverEntry === "string"
+      && Number.isFinite(parsed.startedAt)
+    ) {
+      return parsed as ManagedServerLock;
+    }
+  } catch {
+    return null;
+  }
+  return null;
+}
+
+export function removeServerLock(lockPath: string): void {
+  rmSync(lockPath, { force: true });
+}
+
+export function isProcessAlive(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch (error) {
+    return (error as NodeJS.ErrnoException).code === "EPERM";
+  }
+}
+
+// -ww keeps macOS ps from truncating long commands to the terminal width.
+export async function readProcessCommand(pid: number): Promise<string | null> {
+  try {
+    const { stdout } = await execFileAsync("ps", ["-ww", "-o", "command=", "-p", String(pid)]);
+    return stdout.trim() || null;
+  } catch {
+    return null;
+  }
+}
+
+async function waitForProcessExit(pid: number, timeoutMs: number): Promise<boolean> {
+  const deadline = Date.now() + timeoutMs;
+  while (Date.now() <= deadline) {
+    if (!isProcessAlive(pid)) return true;
+    await delay(ORPHAN_POLL_INTERVAL_MS);
+  }
+  return !isProcessAlive(pid);
+}
+
+export async function cleanupOrphanedServer(input: {
+  lockPath: string;
+  stopTimeoutMs?: number;
+  readCommand?: (pid: number) => Promise<string | null>;
+}): Promise<OrphanCleanupResult> {
+  const lock = readServerLock(input.lockPath);
+  if (!lock) {
+    removeServerLock(input.lockPath);
+    return { status: "none" };
+  }
+
+  if (!isProcessAlive(lock.pid)) {
+    removeServerLock(input.lockPath);
+    return { status: "stale", lock };
+  }
+
+  // Only signal the PID when it still runs our server entry; PIDs get recycled.
+  const command = await (input.readCommand ?? readProcessCommand)(lock.pid);
+  if (!command?.includes(lock.serverEntry)) {
+    removeServerLock(input.lockPath);
+    return { status: "foreign", lock };
+  }
+
+  const stopTimeoutMs = input.stopTimeoutMs ?? ORPHAN_STOP_TIMEOUT_MS;
+  signalProcessGroup(lock.pid, "SIGTERM");
+  if (!(await waitForProcessExit(lock.pid, stopTimeoutMs))) {
+    signalProcessGroup(lock.pid, "SIGKILL");
+    await waitForProcessExit(lock.pid, stopTimeoutMs);
+  }
+
+  removeServerLock(input.lockPath);
+  return { status: "killed", lock };
+}
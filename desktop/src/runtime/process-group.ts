@@ -0,0 +1,47 @@
+export function signalProcessGroup(pid: number, signal: NodeJS.Signals): boolean {
+  if (process.platform !== "win32") {
+    try {
+      process.kill(-pid, signal);
+      return true;
+    } catch {
+      // Fall through to the leader when the group is already gone or was never created.
+    }
+  }
+  try {
+    process.kill(pid, signal);
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+export function isProcessGroupAlive(pgid: number): boolean {
+  if (process.platform === "win32") return false;
+  try {
+    process.kill(-pgid, 0);
+    return true;
+  } catch (error) {
+    return (error as NodeJS.ErrnoException).code === "EPERM";
+  }
+}
+
+// Stops whatever is left of a group after its leader exited: SIGTERM, then SIGKILL once the grace period lapses.
+export async function terminateProcessGroup(pgid: number, graceMs: number, pollMs = 100): Promise<void> {
+  if (!isProcessGroupAlive(pgid)) return;
+  try {
+    process.kill(-pgid, "SIGTERM");
+  } catch {
+    return;
+  }
+
+  const deadline = Date.now() + graceMs;
+  while (Date.now() < deadline) {
+    await new Promise((resolve) => setTimeout(resolve, pollMs));
+    if (!isProcessGroupAlive(pgid)) return;
+  }
+  try {
+    process.kill(-pgid, "SIGKILL");
+  } catch {
+    // The group emptied between the last poll and the kill.
+  }
+}
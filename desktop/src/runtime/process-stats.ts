@@ -0,0 +1,43 @@
+import { execFile } from "node:child_process";
+import { promisify } from "node:util";
+
+const execFileAsync = promisify(execFile);
+
+export type ManagedProcessStats = {
+  label: string;
+  pid: number;
+  cpuPercent: number | null;
+  rssBytes: number | null;
+  uptimeSeconds: number;
+};
+
+export function parsePsStatsOutput(stdout: string): { cpuPercent: number; rssBytes: number } | null {
+  const line = stdout.trim().split("\n").pop()?.trim();
+  if (!line) return null;
+  const [rssKb, cpu] = line.split(/\s+/).map((value) => Number.parseFloat(value));
+  if (!Number.isFinite(rssKb) || !Number.isFinite(cpu)) return null;
+  return { cpuPercent: cpu, rssBytes: Math.round(rssKb * 1024) };
+}
+
+export async function readProcessStats(input: {
+  label: string;
+  pid: number;
+  startedAt: number;
+  now?: number;
+}): Promise<ManagedProcessStats> {
+  let usage: ReturnType<typeof parsePsStatsOutput> = null;
+  try {
+    const { stdout } = await execFileAsync("ps", ["-o", "rss=,%cpu=", "-p", String(input.pid)]);
+    usage = parsePsStatsOutput(stdout);
+  } catch {
+    usage = null;
+  }
+
+  return {
+    label: input.label,
+    pid: input.pid,
+    cpuPercent: usage?.cpuPercent ?? null,
+    rssBytes: usage?.rssBytes ?? null,
+    uptimeSeconds: Math.max(0, Math.floor(((input.now ?? Date.now()) - input.startedAt) / 1000)),
+  };
+}
@@ -0,0 +1,62 @@
+import { createWriteStream, openSync, renameSync, statSync, type WriteStream } from "node:fs";
+
+const DEFAULT_MAX_BYTES = 5 * 1024 * 1024;
+
+export type ServerOutputLog = {
+  write(text: string): void;
+  end(): void;
+};
+
+function readFileSize(filePath: string): number {
+  try {
+    return statSync(filePath).size;
+  } catch {
+    return 0;
+  }
+}
+
+// Appends across server restarts and keeps one rotated copy (`.1`) once the file passes `maxBytes`.
+export function createServerOutputLog(input: { filePath: string; maxBytes?: number }): ServerOutputLog {
+  const maxBytes = input.maxBytes ?? DEFAULT_MAX_BYTES;
+  let size = readFileSize(input.filePath);
+
+  const rotate = () => {
+    try {
+      renameSync(input.filePath, `${input.filePath}.1`);
+    } catch {
+      // Nothing to rotate yet.
+    }
+    size = 0;
+  };
+
+  // Open synchronously so a later rename cannot race the stream's own asynchronous open.
+  // The server is forked before this opens, so an unwritable log drops raw output instead of failing startup.
+  const open = (): WriteStream | null => {
+    try {
+      const stream = createWriteStream(input.filePath, { fd: openSync(input.filePath, "a") });
+      stream.on("error", () => undefined);
+      return stream;
+    } catch {
+      return null;
+    }
+  };
+
+  if (size >= maxBytes) rotate();
+  let stream = open();
+
+  return {
+    write(text) {
+      if (size >= maxBytes) {
+        // Pending writes still land in the renamed file because the old stream keeps its descriptor.
+        stream?.end();
+        rotate();
+        stream = open();
+      }
+      stream?.write(text);
+      size += Buffer.byteLength(text);
+    },
+    end() {
+      stream?.end();
+    },
+  };
+}
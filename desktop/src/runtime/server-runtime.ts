@@ -2,12 +2,26 @@ import { fork, type ChildProcess } from "node:child_process";
 import { existsSync, mkdirSync, readdirSync } from "node:fs";
 import os from "node:os";
 import path from "node:path";
+import { stripVTControlCharacters } from "node:util";
 import detectPort from "detect-port";
+import type { DesktopPortRange, DesktopServerLogLevel } from "./desktop-settings.js";
+import {
+  cleanupOrphanedServer,
+  removeServerLock,
+  resolveServerLockPath,
+  writeServerLock,
+  type OrphanCleanupResult,
+} from "./orphan-process.js";
+import { signalProcessGroup, terminateProcessGroup } from "./process-group.js";
+import { analyzeStartupFailure, type StartupFailureKind } from "./failure-analysis.js";
+import { createServerOutputLog, type ServerOutputLog } from "./server-output-log.js";
 
 const DEFAULT_SERVER_PORT = 3100;
 const HEALTH_PATH = "/api/health";
 const HEALTH_POLL_INTERVAL_MS = 500;
+const HEALTH_REQUEST_TIMEOUT_MS = 5_000;
 const HEALTH_TIMEOUT_MS = 90_000;
+const STARTUP_PROGRESS_INTERVAL_MS = 5_000;
 const SERVER_STOP_TIMEOUT_MS = 10_000;
 const LOG_LINE_LIMIT = 200;
 const DEFAULT_INSTANCE_ID = "default";
@@ -37,7 +51,16 @@ export type ManagedServerStartInput = {
   userDataPath: string;
   serverRoot: string;
   preferredPort?: number;
+  portRange?: DesktopPortRange | null;
   startupTimeoutMs?: number;
+  healthPath?: string;
+  stopTimeoutMs?: number;
+  startupProgressIntervalMs?: number;
+  maxOldSpaceMb?: number | null;
+  env?: Record<string, string>;
+  onStartupProgress?: (elapsedMs: number) => void;
+  logLevel?: DesktopServerLogLevel;
+  debug?: boolean;
 };
 
 export type ManagedServerExitInfo = {
@@ -45,16 +68,65 @@ export type ManagedServerExitInfo = {
   signal: NodeJS.Signals | null;
 };
 
+export type ManagedServerLogStream = "stdout" | "stderr";
+
+export type ManagedServerLogLine = {
+  stream: ManagedServerLogStream;
+  line: string;
+};
+
+export type ManagedServerHealth = {
+  status: string;
+  version: string | null;
+  deploymentMode: string | null;
+  features: Record<string, boolean>;
+};
+
 export type ManagedServerHandle = {
   apiUrl: string;
+  port: number;
+  pid: number | null;
+  startedAt: number;
+  health: ManagedServerHealth;
+  orphanCleanup: OrphanCleanupResult;
   paperclipHome: string;
   logsDir: string;
   serverLogPath: string;
   whenExit: Promise<ManagedServerExitInfo>;
-  getRecentLogLines(): string[];
+  getRecentLogLines(limit?: number): string[];
+  onLogLine(listener: (entry: ManagedServerLogLine) => void): () => void;
   stop(): Promise<void>;
 };
 
+export class ManagedServerStartupError extends Error {
+  readonly reason: string;
+  readonly failureKind: StartupFailureKind;
+  readonly remediation: string;
+  readonly logLines: string[];
+  readonly commandLine: string;
+
+  constructor(input: {
+    reason: string;
+    failureKind: StartupFailureKind;
+    remediation: string;
+    logLines: string[];
+    commandLine: string;
+  }) {
+    const details = `${input.reason}\n\nCommand: ${input.commandLine}`;
+    super(
+      input.logLines.length > 0
+        ? `${details}\n\nRecent server logs:\n${input.logLines.join("\n")}`
+        : details,
+    );
+    this.name = "ManagedServerStartupError";
+    this.reason = input.reason;
+    this.failureKind = input.failureKind;
+    this.remediation = input.remediation;
+    this.logLines = input.logLines;
+    this.commandLine = input.commandLine;
+  }
+}
+
 function parseVersionSegments(version: string): number[] {
   const normalized = version.trim().replace(/^[^\d]*/, "");
   if (!normalized) return [];
@@ -112,25 +184,39 @@ function delay(ms: number): Promise<void> {
 }
 
 function createLogBuffer(limit = LOG_LINE_LIMIT) {
-  const lines: string[] = [];
-  let partial = "";
+  const entries: ManagedServerLogLine[] = [];
+  const partials: Record<ManagedServerLogStream, string> = { stdout: "", stderr: "" };
+  const listeners = new Set<(entry: ManagedServerLogLine) => void>();
 
-  const pushLine = (line: string) => {
-    const trimmed = line.trim();
+  const pushLine = (stream: ManagedServerLogStream, line: string) => {
+    const trimmed = stripVTControlCharacters(line).trim();
     if (!trimmed) return;
-    lines.push(trimmed);
-    if (lines.length > limit) lines.splice(0, lines.length - limit);
+    const entry = { stream, line: trimmed };
+    entries.push(entry);
+    if (entries.length > limit) entries.splice(0, entries.length - limit);
+    for (const listener of listeners) listener(entry);
   };
 
   return {
-    append(chunk: string) {
-      partial += chunk.replace(/\r/g, "");
-      const parts = partial.split("\n");
-      partial = parts.pop() ?? "";
-      for (const line of parts) pushLine(line);
+    append(stream: ManagedServerLogStream, chunk: string) {
+      partials[stream] += chunk.replace(/\r/g, "");
+      const parts = partials[stream].split("\n");
+      partials[stream] = parts.pop() ?? "";
+      for (const line of parts) pushLine(stream, line);
+    },
+    snapshot(maxLines = limit) {
+      const lines = entries.map((entry) => entry.line);
+      for (const partial of Object.values(partials)) {
+        const trimmed = stripVTControlCharacters(partial).trim();
+        if (trimmed) lines.push(trimmed);
+      }
+      return lines.slice(-maxLines);
     },
-    snapshot() {
-      return partial.trim() ? [...lines, partial.trim()] : [...lines];
+    subscribe(listener: (entry: ManagedServerLogLine) => void) {
+      listeners.add(listener);
+      return () => {
+        listeners.delete(listener);
+      };
     },
   };
 }
@@ -147,6 +233,10 @@ export function resolveDesktopServerLogPath(paperclipHome: string, instanceId =
   return path.resolve(resolveDesktopLogsDir(paperclipHome, instanceId), "server.log");
 }
 
+export function resolveDesktopServerOutputLogPath(paperclipHome: string, instanceId = DEFAULT_INSTANCE_ID): string {
+  return path.resolve(resolveDesktopLogsDir(paperclipHome, instanceId), "server-output.log");
+}
+
 export function resolveDesktopServerRoot(input: {
   isPackaged: boolean;
   appPath: string;
@@ -180,10 +270,15 @@ export function buildDesktopServerEnv(input: {
   paperclipHome: string;
   port: number;
   instanceId?: string;
+  logLevel?: DesktopServerLogLevel;
+  debug?: boolean;
+  extraEnv?: Record<string, string>;
 }): NodeJS.ProcessEnv {
   const instanceId = input.instanceId ?? DEFAULT_INSTANCE_ID;
+  const logLevel = input.debug ? "trace" : input.logLevel ?? "debug";
+  const baseEnv = { ...input.baseEnv, ...input.extraEnv };
   return {
-    ...input.baseEnv,
+    ...baseEnv,
     HOST: "127.0.0.1",
     PORT: String(input.port),
     SERVE_UI: "true",
@@ -193,24 +288,72 @@ export function buildDesktopServerEnv(input: {
     PAPERCLIP_HOME: input.paperclipHome,
     PAPERCLIP_INSTANCE_ID: instanceId,
     PAPERCLIP_OPEN_ON_LISTEN: "false",
-    PATH: augmentDesktopPath(input.baseEnv.PATH, input.baseEnv.HOME ?? os.homedir()),
+    PAPERCLIP_LOG_LEVEL: logLevel,
+    PATH: augmentDesktopPath(baseEnv.PATH, baseEnv.HOME ?? os.homedir()),
   };
 }
 
+export function buildServerExecArgv(input: { debug?: boolean; maxOldSpaceMb?: number | null }): string[] {
+  const execArgv: string[] = [];
+  if (input.maxOldSpaceMb) execArgv.push(`--max-old-space-size=${input.maxOldSpaceMb}`);
+  if (input.debug) execArgv.push("--trace-warnings", "--trace-uncaught");
+  return execArgv;
+}
+
+function quoteShellArg(part: string): string {
+  if (/^[\w@%+=:,./-]+$/.test(part)) return part;
+  return `'${part.replace(/'/g, `'\\''`)}'`;
+}
+
+export function formatCommandLine(parts: string[], env: Record<string, string> = {}): string {
+  return [
+    ...Object.entries(env).map(([key, value]) => `${key}=${quoteShellArg(value)}`),
+    ...parts.map(quoteShellArg),
+  ].join(" ");
+}
+
 export function formatExitReason(exitInfo: ManagedServerExitInfo): string {
   if (exitInfo.signal) return `signal ${exitInfo.signal}`;
   return `exit code ${exitInfo.code ?? "unknown"}`;
 }
 
+export function parseServerHealth(payload: unknown): ManagedServerHealth {
+  const record = payload && typeof payload === "object" ? payload as Record<string, unknown> : {};
+  const rawFeatures = record.features && typeof record.features === "object"
+    ? record.features as Record<string, unknown>
+    : {};
+  const features: Record<string, boolean> = {};
+  for (const [key, value] of Object.entries(rawFeatures)) {
+    if (typeof value === "boolean") features[key] = value;
+  }
+
+  return {
+    status: typeof record.status === "string" ? record.status : "unknown",
+    version: typeof record.version === "string" ? record.version : null,
+    deploymentMode: typeof record.deploymentMode === "string" ? record.deploymentMode : null,
+    features,
+  };
+}
+
 async function waitForServerHealth(input: {
   apiUrl: string;
+  healthPath: string;
   timeoutMs: number;
+  progressIntervalMs: number;
+  onProgress?: (elapsedMs: number) => void;
   whenExit: Promise<ManagedServerExitInfo>;
-}): Promise<void> {
-  const deadline = Date.now() + input.timeoutMs;
+}): Promise<ManagedServerHealth> {
+  const startedAt = Date.now();
+  const deadline = startedAt + input.timeoutMs;
+  let nextProgressAt = startedAt + input.progressIntervalMs;
   let lastError: string | null = null;
 
   while (Date.now() <= deadline) {
+    if (input.onProgress && Date.now() >= nextProgressAt) {
+      input.onProgress(Date.now() - startedAt);
+      nextProgressAt += input.progressIntervalMs;
+    }
+
     const exitResult = await Promise.race([
       input.whenExit.then((exitInfo) => ({ kind: "exit" as const, exitInfo })),
       delay(HEALTH_POLL_INTERVAL_MS).then(() => ({ kind: "timer" as const })),
@@ -221,10 +364,15 @@ async function waitForServerHealth(input: {
     }
 
     try {
-      const response = await fetch(new URL(HEALTH_PATH, input.apiUrl), {
+      // A bound server stuck in its health handler would otherwise hold the request past the deadline.
+      const requestTimeoutMs = Math.max(1, Math.min(HEALTH_REQUEST_TIMEOUT_MS, deadline - Date.now()));
+      const response = await fetch(new URL(input.healthPath, input.apiUrl), {
         headers: { accept: "application/json" },
+        signal: AbortSignal.timeout(requestTimeoutMs),
       });
-      if (response.ok) return;
+      if (response.ok) {
+        return parseServerHealth(await response.json().catch(() => null));
+      }
       lastError = `Health check returned ${response.status}`;
     } catch (error) {
       lastError = error instanceof Error ? error.message : String(error);
@@ -238,31 +386,69 @@ async function waitForServerHealth(input: {
   );
 }
 
-function attachChildLogs(child: ChildProcess, buffer: ReturnType<typeof createLogBuffer>) {
+// Without a range, detect-port falls back to any free port; with one, the search stays inside it.
+export async function selectServerPort(input: {
+  preferredPort?: number;
+  portRange?: DesktopPortRange | null;
+}): Promise<number> {
+  const preferredPort = input.preferredPort ?? DEFAULT_SERVER_PORT;
+  if (!input.portRange) return detectPort(preferredPort);
+
+  const { start, end } = input.portRange;
+  const size = end - start + 1;
+  const first = preferredPort >= start && preferredPort <= end ? preferredPort : start;
+  for (let offset = 0; offset < size; offset += 1) {
+    const candidate = start + ((first - start + offset) % size);
+    if ((await detectPort(candidate)) === candidate) return candidate;
+  }
+  throw new Error(`No free port for the Paperclip server in ${start}-${end}.`);
+}
+
+function attachChildLogs(
+  child: ChildProcess,
+  buffer: ReturnType<typeof createLogBuffer>,
+  output: ServerOutputLog,
+) {
   child.stdout?.setEncoding("utf8");
   child.stderr?.setEncoding("utf8");
   child.stdout?.on("data", (chunk: string | Buffer) => {
-    buffer.append(typeof chunk === "string" ? chunk : chunk.toString("utf8"));
+    const text = typeof chunk === "string" ? chunk : chunk.toString("utf8");
+    output.write(text);
+    buffer.append("stdout", text);
   });
   child.stderr?.on("data", (chunk: string | Buffer) => {
-    buffer.append(typeof chunk === "string" ? chunk : chunk.toString("utf8"));
+    const text = typeof chunk === "string" ? chunk : chunk.toString("utf8");
+    output.write(text);
+    buffer.append("stderr", text);
   });
 }
 
-async function stopChildProcess(child: ChildProcess, whenExit: Promise<ManagedServerExitInfo>): Promise<void> {
+function signalChild(child: ChildProcess, signal: NodeJS.Signals) {
+  if (child.pid) {
+    signalProcessGroup(child.pid, signal);
+    return;
+  }
+  child.kill(signal);
+}
+
+async function stopChildProcess(
+  child: ChildProcess,
+  whenExit: Promise<ManagedServerExitInfo>,
+  timeoutMs = SERVER_STOP_TIMEOUT_MS,
+): Promise<void> {
   if (child.exitCode !== null || child.signalCode !== null) {
     await whenExit.catch(() => undefined);
     return;
   }
 
-  child.kill("SIGTERM");
+  signalChild(child, "SIGTERM");
   const result = await Promise.race([
     whenExit.then(() => "exited" as const),
-    delay(SERVER_STOP_TIMEOUT_MS).then(() => "timeout" as const),
+    delay(timeoutMs).then(() => "timeout" as const),
   ]);
 
   if (result === "timeout" && child.exitCode === null && child.signalCode === null) {
-    child.kill("SIGKILL");
+    signalChild(child, "SIGKILL");
     await whenExit.catch(() => undefined);
   }
 }
@@ -275,55 +461,94 @@ export async function startManagedServer(input: ManagedServerStartInput): Promis
   const paperclipHome = resolveDesktopPaperclipHome(input.userDataPath);
   const logsDir = resolveDesktopLogsDir(paperclipHome);
   const serverLogPath = resolveDesktopServerLogPath(paperclipHome);
+  const lockPath = resolveServerLockPath(path.dirname(logsDir));
   mkdirSync(logsDir, { recursive: true });
 
-  const port = await detectPort(input.preferredPort ?? DEFAULT_SERVER_PORT);
-  const apiUrl = `http://127.0.0.1:${port}`;
   const serverEntry = path.resolve(input.serverRoot, "dist", "index.js");
+  const orphanCleanup = await cleanupOrphanedServer({ lockPath, stopTimeoutMs: input.stopTimeoutMs });
+  const port = await selectServerPort({ preferredPort: input.preferredPort, portRange: input.portRange });
+  const apiUrl = `http://127.0.0.1:${port}`;
   const env = buildDesktopServerEnv({
     baseEnv: process.env,
     paperclipHome,
     port,
+    logLevel: input.logLevel,
+    debug: input.debug,
+    extraEnv: input.env,
   });
   const logBuffer = createLogBuffer();
+  const startedAt = Date.now();
+  const execArgv = buildServerExecArgv({ debug: input.debug, maxOldSpaceMb: input.maxOldSpaceMb });
+  // fork() runs the Electron binary as plain Node, which a copied command only reproduces with this flag.
+  const commandLine = formatCommandLine(
+    [process.execPath, ...execArgv, serverEntry],
+    process.versions.electron ? { ELECTRON_RUN_AS_NODE: "1" } : {},
+  );
   const child = fork(serverEntry, [], {
     cwd: input.serverRoot,
     env,
+    detached: process.platform !== "win32",
+    execArgv,
     stdio: ["ignore", "pipe", "pipe", "ipc"],
   });
 
-  attachChildLogs(child, logBuffer);
+  const serverOutput = createServerOutputLog({ filePath: resolveDesktopServerOutputLogPath(paperclipHome) });
+  child.once("close", () => serverOutput.end());
+  attachChildLogs(child, logBuffer, serverOutput);
+
+  if (child.pid) {
+    writeServerLock(lockPath, { pid: child.pid, port, serverEntry, startedAt });
+  }
 
   const whenExit = new Promise<ManagedServerExitInfo>((resolve) => {
     child.once("exit", (code, signal) => {
-      resolve({ code, signal });
+      // Helpers (plugin workers, adapter CLIs, embedded Postgres) share the group and outlive a crashed leader.
+      const groupCleanup = child.pid && process.platform !== "win32"
+        ? terminateProcessGroup(child.pid, input.stopTimeoutMs ?? SERVER_STOP_TIMEOUT_MS)
+        : Promise.resolve();
+      void groupCleanup.finally(() => {
+        removeServerLock(lockPath);
+        resolve({ code, signal });
+      });
     });
   });
 
+  let health: ManagedServerHealth;
   try {
-    await waitForServerHealth({
+    health = await waitForServerHealth({
       apiUrl,
+      healthPath: input.healthPath ?? HEALTH_PATH,
       timeoutMs: input.startupTimeoutMs ?? HEALTH_TIMEOUT_MS,
+      progressIntervalMs: input.startupProgressIntervalMs ?? STARTUP_PROGRESS_INTERVAL_MS,
+      onProgress: input.onStartupProgress,
       whenExit,
     });
   } catch (error) {
-    await stopChildProcess(child, whenExit);
-    const reason = error instanceof Error ? error.message : String(error);
+    const exitedDuringStartup = child.exitCode !== null || child.signalCode !== null;
+    const exitInfo = exitedDuringStartup ? { code: child.exitCode, signal: child.signalCode } : null;
+    await stopChildProcess(child, whenExit, input.stopTimeoutMs);
     const logLines = logBuffer.snapshot();
-    throw new Error(
-      logLines.length > 0
-        ? `${reason}\n\nRecent server logs:\n${logLines.join("\n")}`
-        : reason,
-    );
+    throw new ManagedServerStartupError({
+      reason: error instanceof Error ? error.message : String(error),
+      logLines,
+      commandLine,
+      ...analyzeStartupFailure({ exitInfo, logLines }),
+    });
   }
 
   return {
     apiUrl,
+    port,
+    pid: child.pid ?? null,
+    startedAt,
+    health,
+    orphanCleanup,
     paperclipHome,
     logsDir,
     serverLogPath,
     whenExit,
-    getRecentLogLines: () => logBuffer.snapshot(),
-    stop: () => stopChildProcess(child, whenExit),
+    getRecentLogLines: (limit) => logBuffer.snapshot(limit),
+    onLogLine: (listener) => logBuffer.subscribe(listener),
+    stop: () => stopChildProcess(child, whenExit, input.stopTimeoutMs),
   };
 }
@@ -0,0 +1,179 @@
+import {
+  formatExitReason,
+  ManagedServerStartupError,
+  type ManagedServerExitInfo,
+  type ManagedServerHandle,
+} from "./server-runtime.js";
+
+const DEFAULT_MAX_RESTARTS = 5;
+const DEFAULT_BASE_DELAY_MS = 1_000;
+const DEFAULT_MAX_DELAY_MS = 30_000;
+const DEFAULT_STABLE_UPTIME_MS = 60_000;
+
+export type ManagedServerCrashEvent = {
+  reason: string;
+  exitInfo: ManagedServerExitInfo | null;
+  attempt: number;
+  willRestart: boolean;
+  delayMs: number;
+  logLines: string[];
+};
+
+export type ManagedServerSupervisorInput = {
+  start(previous: ManagedServerHandle | null): Promise<ManagedServerHandle>;
+  isAutoRestartEnabled(): boolean;
+  maxRestarts?: number;
+  baseDelayMs?: number;
+  maxDelayMs?: number;
+  stableUptimeMs?: number;
+  onCrashed?(event: ManagedServerCrashEvent): void;
+  onRestarted?(event: { handle: ManagedServerHandle; attempt: number }): void;
+  onGaveUp?(event: ManagedServerCrashEvent): void;
+};
+
+export type ManagedServerSupervisor = {
+  getHandle(): ManagedServerHandle | null;
+  restart(): Promise<void>;
+  stop(): Promise<void>;
+};
+
+type ManagedServerCrash = Pick<ManagedServerCrashEvent, "reason" | "exitInfo" | "logLines">;
+
+function describeStartFailure(error: unknown): ManagedServerCrash {
+  return {
+    reason: error instanceof ManagedServerStartupError ? error.reason : String(error),
+    exitInfo: null,
+    logLines: error instanceof ManagedServerStartupError ? error.logLines : [],
+  };
+}
+
+export function computeRestartDelayMs(
+  attempt: number,
+  baseDelayMs = DEFAULT_BASE_DELAY_MS,
+  maxDelayMs = DEFAULT_MAX_DELAY_MS,
+): number {
+  const exponent = Math.max(0, attempt - 1);
+  return Math.min(maxDelayMs, baseDelayMs * 2 ** exponent);
+}
+
+export async function startSupervisedServer(input: ManagedServerSupervisorInput): Promise<ManagedServerSupervisor> {
+  const maxRestarts = input.maxRestarts ?? DEFAULT_MAX_RESTARTS;
+  const stableUptimeMs = input.stableUptimeMs ?? DEFAULT_STABLE_UPTIME_MS;
+  let handle: ManagedServerHandle | null = await input.start(null);
+  let startedAt = Date.now();
+  let attempt = 0;
+  let stopped = false;
+  let recovery: Promise<void> | null = null;
+  let cancelBackoff: (() => void) | null = null;
+
+  const waitForBackoff = (ms: number) =>
+    new Promise<void>((resolve) => {
+      const timer = setTimeout(done, ms);
+      function done() {
+        clearTimeout(timer);
+        cancelBackoff = null;
+        resolve();
+      }
+      cancelBackoff = done;
+    });
+
+  const recover = async (previous: ManagedServerHandle, firstCrash: ManagedServerCrash) => {
+    if (Date.now() - startedAt >= stableUptimeMs) attempt = 0;
+    let crash = firstCrash;
+
+    while (!stopped) {
+      attempt += 1;
+      const willRestart = input.isAutoRestartEnabled() && attempt <= maxRestarts;
+      const event: ManagedServerCrashEvent = {
+        ...crash,
+        attempt,
+        willRestart,
+        delayMs: willRestart ? computeRestartDelayMs(attempt, input.baseDelayMs, input.maxDelayMs) : 0,
+      };
+      input.onCrashed?.(event);
+      if (!willRestart) {
+        input.onGaveUp?.(event);
+        return;
+      }
+
+      await waitForBackoff(event.delayMs);
+      if (stopped) return;
+
+      try {
+        const next = await input.start(previous);
+        if (stopped) {
+          await next.stop();
+          return;
+        }
+        handle = next;
+        startedAt = Date.now();
+        watch(next);
+        input.onRestarted?.({ handle: next, attempt });
+        return;
+      } catch (error) {
+        crash = describeStartFailure(error);
+      }
+    }
+  };
+
+  const watch = (current: ManagedServerHandle) => {
+    void current.whenExit.then((exitInfo) => {
+      if (stopped || handle !== current) return;
+      handle = null;
+      recovery = recover(current, {
+        reason: formatExitReason(exitInfo),
+        exitInfo,
+        logLines: current.getRecentLogLines(),
+      }).finally(() => {
+        recovery = null;
+      });
+    });
+  };
+
+  // Intentional restarts (e.g. after a settings change) reuse the crash path only if the new server fails to start.
+  const restart = async (current: ManagedServerHandle) => {
+    handle = null;
+    await current.stop();
+    if (stopped) return;
+    try {
+      const next = await input.start(current);
+      if (stopped) {
+        await next.stop();
+        return;
+      }
+      handle = next;
+      startedAt = Date.now();
+      attempt = 0;
+      watch(next);
+      input.onRestarted?.({ handle: next, attempt: 0 });
+    } catch (error) {
+      await recover(current, describeStartFailure(error));
+    }
+  };
+
+  watch(handle);
+
+  return {
+    getHandle: () => handle,
+    restart: async () => {
+      // A crash recovery already in flight starts the server with fresh settings.
+      if (stopped || recovery || !handle) {
+        if (recovery) await recovery;
+        return;
+      }
+      recovery = restart(handle).finally(() => {
+        recovery = null;
+      });
+      await recovery;
+    },
+    stop: async () => {
+      stopped = true;
+      cancelBackoff?.();
+      const current = handle;
+      handle = null;
+      if (current) await current.stop();
+      // A restart may still be waiting on health; it stops its own server once it sees `stopped`.
+      if (recovery) await recovery;
+    },
+  };
+}
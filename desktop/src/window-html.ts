@@ -132,25 +132,33 @@ export function toDataUrl(html: string): string {
   return `data:text/html;charset=utf-8,${encodeURIComponent(html)}`;
 }
 
+export const SPLASH_STATUS_ELEMENT_ID = "startup-status";
+
 export function renderSplashHtml(): string {
   return renderShell({
     eyebrow: '<span class="spinner" aria-hidden="true"></span> Starting local runtime',
     title: "Launching Paperclip",
-    body: "Preparing the local server, storage, and static board UI. This window closes automatically when the control plane is ready.",
+    body: `
+      <p>Preparing the local server, storage, and static board UI. This window closes automatically when the control plane is ready.</p>
+      <p id="${SPLASH_STATUS_ELEMENT_ID}" role="status"></p>
+    `,
   });
 }
 
 export function renderStartupErrorHtml(input: {
   reason: string;
   logLines: string[];
+  remediation?: string;
 }): string {
   const logText = input.logLines.length > 0 ? escapeHtml(input.logLines.join("\n")) : "No server logs captured.";
+  const guidance = input.remediation
+    ?? "Check the local runtime logs or open the Paperclip data folder to inspect the instance state.";
   return renderShell({
     eyebrow: "Startup failed",
     title: "Paperclip could not start",
     body: `
       <p>${escapeHtml(input.reason)}</p>
-      <p>Check the local runtime logs or open the Paperclip data folder to inspect the instance state.</p>
+      <p>${escapeHtml(guidance)}</p>
       <pre>${logText}</pre>
     `,
     tone: "danger",
@@ -20,6 +20,28 @@ fs.mkdirSync(logDir, { recursive: true });
 
 const logFile = path.join(logDir, "server.log");
 
+const LOG_LEVEL_VALUES: Record<string, number> = {
+  trace: 10,
+  debug: 20,
+  info: 30,
+  warn: 40,
+  error: 50,
+  fatal: 60,
+};
+
+function resolveConfiguredLogLevel(): string | null {
+  const raw = process.env.PAPERCLIP_LOG_LEVEL?.trim().toLowerCase();
+  return raw && raw in LOG_LEVEL_VALUES ? raw : null;
+}
+
+const configuredLogLevel = resolveConfiguredLogLevel();
+const fileLogLevel = configuredLogLevel ?? "debug";
+// Verbose levels go to server.log only; the console stays at info or quieter.
+const consoleLogLevel =
+  configuredLogLevel && LOG_LEVEL_VALUES[configuredLogLevel] > LOG_LEVEL_VALUES.info ? configuredLogLevel : "info";
+const rootLogLevel =
+  LOG_LEVEL_VALUES[fileLogLevel] < LOG_LEVEL_VALUES[consoleLogLevel] ? fileLogLevel : consoleLogLevel;
+
 const sharedOpts = {
   translateTime: "SYS:HH:MM:ss",
   ignore: "pid,hostname",
@@ -27,19 +49,19 @@ const sharedOpts = {
 };
 
 export const logger = pino({
-  level: "debug",
+  level: rootLogLevel,
   redact: ["req.headers.authorization"],
 }, pino.transport({
   targets: [
     {
       target: "pino-pretty",
       options: { ...sharedOpts, ignore: "pid,hostname,req,res,responseTime", colorize: true, destination: 1 },
-      level: "info",
+      level: consoleLogLevel,
     },
     {
       target: "pino-pretty",
       options: { ...sharedOpts, colorize: false, destination: logFile, mkdir: true },
-      level: "debug",
+      level: fileLogLevel,
     },
   ],
 }));
@@ -1,9 +1,107 @@
+export interface PaperclipDesktopInstanceInfo {
+  apiUrl: string;
+  port: number;
+  paperclipHome: string;
+  health: {
+    status: string;
+    version: string | null;
+    deploymentMode: string | null;
+    features: Record<string, boolean>;
+  };
+}
+
+export interface PaperclipDesktopServerLogLine {
+  stream: "stdout" | "stderr";
+  line: string;
+}
+
+export interface PaperclipDesktopServerCrashEvent {
+  reason: string;
+  exitCode: number | null;
+  signal: string | null;
+  logLines: string[];
+  attempt: number;
+  willRestart: boolean;
+  delayMs: number;
+}
+
+export interface PaperclipDesktopServerRestartEvent {
+  apiUrl: string;
+  attempt: number;
+}
+
+export interface PaperclipDesktopProcessStats {
+  label: string;
+  pid: number;
+  cpuPercent: number | null;
+  rssBytes: number | null;
+  uptimeSeconds: number;
+}
+
+export interface PaperclipDesktopSettings {
+  serverLogLevel: "trace" | "debug" | "info" | "warn" | "error";
+  serverDebug: boolean;
+  serverAutoRestart: boolean;
+  serverStopGracePeriodMs: number;
+  serverStartupTimeoutMs: number;
+  serverHealthPath: string;
+  serverMaxOldSpaceMb: number | null;
+  serverPortRange: { start: number; end: number } | null;
+  serverEnv: Record<string, string>;
+}
+
+export interface PaperclipDesktopEventEnvelope<Type extends string, Payload> {
+  sequence: number;
+  type: Type;
+  at: string;
+  payload: Payload;
+}
+
+export type PaperclipDesktopServerLogEvent = PaperclipDesktopEventEnvelope<"server-log", PaperclipDesktopServerLogLine>;
+export type PaperclipDesktopServerStartingEvent = PaperclipDesktopEventEnvelope<"server-starting", { elapsedMs: number }>;
+export type PaperclipDesktopServerCrashedEvent = PaperclipDesktopEventEnvelope<
+  "server-crashed",
+  PaperclipDesktopServerCrashEvent
+>;
+export type PaperclipDesktopServerRestartedEvent = PaperclipDesktopEventEnvelope<
+  "server-restarted",
+  PaperclipDesktopServerRestartEvent
+>;
+export type PaperclipDesktopServerOrphanStoppedEvent = PaperclipDesktopEventEnvelope<
+  "server-orphan-stopped",
+  { pid: number; port: number; startedAt: string }
+>;
+
+// Live events carry the same sequence as replayed ones, so late subscribers can replay with afterSequence and dedupe.
+export type PaperclipDesktopEvent =
+  | PaperclipDesktopServerStartingEvent
+  | PaperclipDesktopServerCrashedEvent
+  | PaperclipDesktopServerRestartedEvent
+  | PaperclipDesktopServerOrphanStoppedEvent;
+
 export interface PaperclipDesktopBridge {
   openLogs?: () => Promise<void>;
   openDataFolder?: () => Promise<void>;
   reloadApp?: () => Promise<void>;
   chooseDirectory?: () => Promise<string | null>;
   revealPath?: (targetPath: string) => Promise<void>;
+  getInstanceInfo?: () => Promise<PaperclipDesktopInstanceInfo | null>;
+  getServerLogs?: (limit?: number) => Promise<string[]>;
+  replayEvents?: (filter?: {
+    types?: PaperclipDesktopEvent["type"][];
+    afterSequence?: number;
+  }) => Promise<PaperclipDesktopEvent[]>;
+  readEventJournal?: (since?: string) => Promise<PaperclipDesktopEvent[]>;
+  getProcessStats?: () => Promise<PaperclipDesktopProcessStats[]>;
+  getSettings?: () => Promise<PaperclipDesktopSettings>;
+  updateSettings?: (
+    patch: Partial<Omit<PaperclipDesktopSettings, "serverEnv" | "serverMaxOldSpaceMb" | "serverPortRange">>,
+  ) => Promise<PaperclipDesktopSettings>;
+  onServerLog?: (listener: (event: PaperclipDesktopServerLogEvent) => void) => () => void;
+  onServerStarting?: (listener: (event: PaperclipDesktopServerStartingEvent) => void) => () => void;
+  onServerCrashed?: (listener: (event: PaperclipDesktopServerCrashedEvent) => void) => () => void;
+  onServerRestarted?: (listener: (event: PaperclipDesktopServerRestartedEvent) => void) => () => void;
+  onServerOrphanStopped?: (listener: (event: PaperclipDesktopServerOrphanStoppedEvent) => void) => () => void;
 }
 
 export function getPaperclipDesktopBridge(): PaperclipDesktopBridge | null {